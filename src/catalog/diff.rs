@@ -0,0 +1,410 @@
+//! Field-level changes between two versions of an item, or two versions of the whole catalog.
+
+use std::fmt::{self, Display};
+
+use crate::{
+    catalog::ItemCatalog,
+    models::item::{Item, ItemId, ItemRarity, ItemStat, ItemType},
+};
+
+/// A field of an [`Item`] that can change between game updates.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum ItemField {
+    Name,
+    Type,
+    Description,
+    Equipable,
+    Level,
+    Rarity,
+    Value,
+    Stat1,
+    Stat1Modifier,
+    Stat2,
+    Stat2Modifier,
+    Stat3,
+    Stat3Modifier,
+    CustomItem,
+    Tradable,
+    Locked,
+}
+
+impl Display for ItemField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ItemField::Name => "name",
+            ItemField::Type => "type",
+            ItemField::Description => "description",
+            ItemField::Equipable => "equipable",
+            ItemField::Level => "level",
+            ItemField::Rarity => "rarity",
+            ItemField::Value => "value",
+            ItemField::Stat1 => "stat1",
+            ItemField::Stat1Modifier => "stat1modifier",
+            ItemField::Stat2 => "stat2",
+            ItemField::Stat2Modifier => "stat2modifier",
+            ItemField::Stat3 => "stat3",
+            ItemField::Stat3Modifier => "stat3modifier",
+            ItemField::CustomItem => "custom_item",
+            ItemField::Tradable => "tradable",
+            ItemField::Locked => "locked",
+        })
+    }
+}
+
+/// The value of an [`ItemField`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    Text(String),
+    Description(Option<String>),
+    Number(u32),
+    Flag(bool),
+    Type(ItemType),
+    Rarity(ItemRarity),
+    Stat(Option<ItemStat>),
+    Modifier(Option<u32>),
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Text(text) => f.write_str(text),
+            FieldValue::Description(description) => {
+                write!(f, "{:?}", description.as_deref().unwrap_or_default())
+            }
+            FieldValue::Number(number) => write!(f, "{}", number),
+            FieldValue::Flag(flag) => write!(f, "{}", flag),
+            FieldValue::Type(item_type) => write!(f, "{}", item_type),
+            FieldValue::Rarity(rarity) => write!(f, "{}", rarity),
+            FieldValue::Stat(Some(stat)) => write!(f, "{}", stat),
+            FieldValue::Modifier(Some(modifier)) => write!(f, "{}", modifier),
+            FieldValue::Stat(None) | FieldValue::Modifier(None) => f.write_str("none"),
+        }
+    }
+}
+
+/// A single changed field, with its old and new values.
+///
+/// Values are compared as they were decoded, not as they are displayed, so a change that
+/// doesn't show (e.g. an unknown item type the crate has since learnt about) is still reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: ItemField,
+    pub old: FieldValue,
+    pub new: FieldValue,
+}
+
+impl Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.old, self.new)
+    }
+}
+
+/// The changes between two versions of the same item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemDiff {
+    pub id: ItemId,
+    /// The name of the newer version.
+    pub name: String,
+    pub changes: Vec<FieldChange>,
+}
+
+impl ItemDiff {
+    /// Compares two versions of an item. Returns `None` if they are different items.
+    pub fn between(old: &Item, new: &Item) -> Option<Self> {
+        if old.id != new.id {
+            return None;
+        }
+
+        let mut changes = vec![];
+        let mut compare = |field, value: fn(&Item) -> FieldValue| {
+            let (old, new) = (value(old), value(new));
+            if old != new {
+                changes.push(FieldChange { field, old, new });
+            }
+        };
+
+        compare(ItemField::Name, |item| FieldValue::Text(item.name.clone()));
+        compare(ItemField::Type, |item| {
+            FieldValue::Type(item.item_type.clone())
+        });
+        compare(ItemField::Description, |item| {
+            FieldValue::Description(item.description.clone())
+        });
+        compare(ItemField::Equipable, |item| {
+            FieldValue::Flag(item.equipable)
+        });
+        compare(ItemField::Level, |item| FieldValue::Number(item.level));
+        compare(ItemField::Rarity, |item| {
+            FieldValue::Rarity(item.rarity.clone())
+        });
+        compare(ItemField::Value, |item| FieldValue::Number(item.value));
+        compare(ItemField::Stat1, |item| {
+            FieldValue::Stat(item.stat1.clone())
+        });
+        compare(ItemField::Stat1Modifier, |item| {
+            FieldValue::Modifier(item.stat1modifier)
+        });
+        compare(ItemField::Stat2, |item| {
+            FieldValue::Stat(item.stat2.clone())
+        });
+        compare(ItemField::Stat2Modifier, |item| {
+            FieldValue::Modifier(item.stat2modifier)
+        });
+        compare(ItemField::Stat3, |item| {
+            FieldValue::Stat(item.stat3.clone())
+        });
+        compare(ItemField::Stat3Modifier, |item| {
+            FieldValue::Modifier(item.stat3modifier)
+        });
+        compare(ItemField::CustomItem, |item| {
+            FieldValue::Flag(item.custom_item)
+        });
+        compare(ItemField::Tradable, |item| FieldValue::Flag(item.tradable));
+        compare(ItemField::Locked, |item| FieldValue::Flag(item.locked));
+
+        Some(Self {
+            id: new.id,
+            name: new.name.clone(),
+            changes,
+        })
+    }
+
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn change(&self, field: ItemField) -> Option<&FieldChange> {
+        self.changes.iter().find(|change| change.field == field)
+    }
+}
+
+impl Display for ItemDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.id, self.name)?;
+        for change in &self.changes {
+            write!(f, "\n    {}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// The changes between two snapshots of the item catalog, in item id order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CatalogDiff {
+    pub added: Vec<Item>,
+    pub removed: Vec<Item>,
+    /// Only items that actually changed; items that are the same in both snapshots are left out.
+    pub changed: Vec<ItemDiff>,
+}
+
+impl CatalogDiff {
+    pub fn between(old: &ItemCatalog, new: &ItemCatalog) -> Self {
+        let mut diff = Self::default();
+
+        for (id, old_item) in old.items() {
+            match new.get(*id) {
+                Some(new_item) => {
+                    if let Some(item_diff) = ItemDiff::between(old_item, new_item) {
+                        if !item_diff.is_empty() {
+                            diff.changed.push(item_diff);
+                        }
+                    }
+                }
+                None => diff.removed.push(old_item.clone()),
+            }
+        }
+        for (id, new_item) in new.items() {
+            if old.get(*id).is_none() {
+                diff.added.push(new_item.clone());
+            }
+        }
+
+        diff
+    }
+
+    /// Whether nothing was added, removed or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Renders the diff as a change log, e.g.
+///
+/// ```text
+/// Added:
+///   [13] Fire Sword (Rare Weapon)
+/// Changed:
+///   [1] Wooden Stick
+///       value: 20 -> 25
+/// ```
+impl Display for CatalogDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("No changes.");
+        }
+
+        let mut sections = vec![];
+        if !self.added.is_empty() {
+            sections.push(format!("Added:{}", summarise(&self.added)));
+        }
+        if !self.removed.is_empty() {
+            sections.push(format!("Removed:{}", summarise(&self.removed)));
+        }
+        if !self.changed.is_empty() {
+            let changed = self
+                .changed
+                .iter()
+                .map(|diff| format!("\n  {}", diff.to_string().replace('\n', "\n  ")))
+                .collect::<String>();
+            sections.push(format!("Changed:{}", changed));
+        }
+        f.write_str(&sections.join("\n"))
+    }
+}
+
+fn summarise(items: &[Item]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            format!(
+                "\n  [{}] {} ({} {})",
+                item.id, item.name, item.rarity, item.item_type
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_item_diff {
+    use super::*;
+
+    fn wooden_stick() -> Item {
+        Item {
+            id: ItemId::new(1),
+            name: "Wooden Stick".to_string(),
+            item_type: ItemType::Weapon,
+            description: None,
+            equipable: true,
+            level: 1,
+            rarity: ItemRarity::Common,
+            value: 20,
+            stat1: Some(ItemStat::Str),
+            stat1modifier: Some(1),
+            stat2: None,
+            stat2modifier: None,
+            stat3: None,
+            stat3modifier: None,
+            custom_item: false,
+            tradable: true,
+            locked: false,
+            #[cfg(feature = "extra-fields")]
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_item_diff() {
+        let old = wooden_stick();
+        let mut new = wooden_stick();
+        new.value = 25;
+        new.stat2 = Some(ItemStat::Def);
+        new.stat2modifier = Some(2);
+        new.tradable = false;
+
+        let diff = ItemDiff::between(&old, &new).unwrap();
+        assert_eq!(
+            diff.changes,
+            vec![
+                FieldChange {
+                    field: ItemField::Value,
+                    old: FieldValue::Number(20),
+                    new: FieldValue::Number(25)
+                },
+                FieldChange {
+                    field: ItemField::Stat2,
+                    old: FieldValue::Stat(None),
+                    new: FieldValue::Stat(Some(ItemStat::Def))
+                },
+                FieldChange {
+                    field: ItemField::Stat2Modifier,
+                    old: FieldValue::Modifier(None),
+                    new: FieldValue::Modifier(Some(2))
+                },
+                FieldChange {
+                    field: ItemField::Tradable,
+                    old: FieldValue::Flag(true),
+                    new: FieldValue::Flag(false)
+                },
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "[1] Wooden Stick\n    value: 20 -> 25\n    stat2: none -> Def\n    stat2modifier: none -> 2\n    tradable: true -> false"
+        );
+    }
+
+    #[test]
+    fn test_item_diff_compares_decoded_values() {
+        let mut old = wooden_stick();
+        old.item_type = ItemType::Unknown("Wood Axe".into());
+        let mut new = wooden_stick();
+        new.item_type = ItemType::WoodAxe;
+
+        let diff = ItemDiff::between(&old, &new).unwrap();
+        assert_eq!(
+            diff.change(ItemField::Type),
+            Some(&FieldChange {
+                field: ItemField::Type,
+                old: FieldValue::Type(ItemType::Unknown("Wood Axe".into())),
+                new: FieldValue::Type(ItemType::WoodAxe),
+            })
+        );
+        assert_eq!(diff.changes.len(), 1);
+    }
+
+    #[test]
+    fn test_item_diff_different_items() {
+        let mut other = wooden_stick();
+        other.id = ItemId::new(2);
+        assert_eq!(ItemDiff::between(&wooden_stick(), &other), None);
+    }
+
+    #[test]
+    fn test_item_diff_unchanged() {
+        assert!(ItemDiff::between(&wooden_stick(), &wooden_stick())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_catalog_diff() {
+        let mut old = ItemCatalog::new();
+        let mut new = ItemCatalog::new();
+
+        let mut removed = wooden_stick();
+        removed.id = ItemId::new(2);
+        removed.name = "Old Stick".into();
+        let mut added = wooden_stick();
+        added.id = ItemId::new(3);
+        added.name = "Fire Sword".into();
+        added.rarity = ItemRarity::Rare;
+        let mut changed = wooden_stick();
+        changed.value = 25;
+
+        old.items.insert(ItemId::new(1), wooden_stick());
+        old.items.insert(ItemId::new(2), removed);
+        new.items.insert(ItemId::new(1), changed);
+        new.items.insert(ItemId::new(3), added);
+
+        let diff = CatalogDiff::between(&old, &new);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            diff.to_string(),
+            "Added:\n  [3] Fire Sword (Rare Weapon)\nRemoved:\n  [2] Old Stick (Common Weapon)\nChanged:\n  [1] Wooden Stick\n      value: 20 -> 25"
+        );
+        assert_eq!(CatalogDiff::between(&old, &old).to_string(), "No changes.");
+    }
+}
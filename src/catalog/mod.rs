@@ -0,0 +1,423 @@
+//! A local mirror of every item in the game.
+
+pub mod diff;
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    catalog::diff::CatalogDiff,
+    client::SmmoClient,
+    error::{ApiErrorType, Error},
+    models::item::{Item, ItemId},
+};
+
+/// Every item found so far, plus enough progress information to carry on crawling from where the
+/// last crawl stopped.
+///
+/// Items are found by walking item ids upward from 1 with
+/// [`get_item_by_id`](SmmoClient::get_item_by_id). Ids the api reports as not found are skipped;
+/// once [`max_gap`](CrawlOptions::max_gap) of them appear in a row, the crawl assumes it has
+/// passed the last item and the catalog is [complete](Self::is_complete). Items that can't be
+/// decoded are skipped too, and kept in [`failed`](Self::failed).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemCatalog {
+    items: BTreeMap<ItemId, Item>,
+    /// Why each item that couldn't be decoded failed.
+    #[serde(default)]
+    failed: BTreeMap<ItemId, String>,
+    /// The next id to fetch.
+    next_id: u32,
+    /// How many ids in a row, up to `next_id`, were not found.
+    gap: u32,
+    complete: bool,
+}
+
+impl ItemCatalog {
+    pub fn new() -> Self {
+        Self {
+            items: BTreeMap::new(),
+            failed: BTreeMap::new(),
+            next_id: 1,
+            gap: 0,
+            complete: false,
+        }
+    }
+
+    pub fn items(&self) -> &BTreeMap<ItemId, Item> {
+        &self.items
+    }
+
+    pub fn into_items(self) -> BTreeMap<ItemId, Item> {
+        self.items
+    }
+
+    pub fn get(&self, id: ItemId) -> Option<&Item> {
+        self.items.get(&id)
+    }
+
+    /// The ids of items that exist but could not be decoded (most likely because the api changed
+    /// their format), with why. Later crawls don't fetch them again.
+    pub fn failed(&self) -> &BTreeMap<ItemId, String> {
+        &self.failed
+    }
+
+    /// What was added, removed and changed in `newer` compared to this catalog.
+    pub fn diff(&self, newer: &ItemCatalog) -> CatalogDiff {
+        CatalogDiff::between(self, newer)
+    }
+
+    /// Whether the last crawl ran until it found the end of the items.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Loads a catalog previously written by [`save`](Self::save), or by a crawl checkpoint.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CatalogError> {
+        let file = fs::read(path).map_err(CatalogError::Io)?;
+        serde_json::from_slice(&file).map_err(CatalogError::Json)
+    }
+
+    /// Loads the catalog at `path`, or starts a new one if there is no file there yet.
+    pub fn load_or_new(path: impl AsRef<Path>) -> Result<Self, CatalogError> {
+        match Self::load(path) {
+            Err(CatalogError::Io(why)) if why.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            result => result,
+        }
+    }
+
+    /// Writes the catalog to `path` as json. The file is replaced atomically, so an interrupted
+    /// save never leaves a half-written catalog behind.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CatalogError> {
+        let path = path.as_ref();
+        let json = serde_json::to_vec(self).map_err(CatalogError::Json)?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, json).map_err(CatalogError::Io)?;
+        fs::rename(&tmp, path).map_err(CatalogError::Io)
+    }
+
+    /// Fetches items until the end of the items is found, or an item can't be fetched: the request
+    /// failed, the api responded with an error status, or with an error other than
+    /// [`ApiErrorType::ItemNotFound`]. On error the catalog keeps everything found so far (and is
+    /// checkpointed, if configured), so calling this again resumes the crawl. Items that are
+    /// fetched but can't be decoded don't stop the crawl; they are recorded in
+    /// [`failed`](Self::failed) and skipped.
+    ///
+    /// Crawling a complete catalog looks for items added since, starting after the highest id
+    /// found.
+    pub async fn crawl(
+        &mut self,
+        client: &SmmoClient,
+        options: &CrawlOptions,
+    ) -> Result<(), CatalogError> {
+        if self.complete {
+            self.next_id = self
+                .items
+                .keys()
+                .chain(self.failed.keys())
+                .max()
+                .map_or(1, |id| id.inner() + 1);
+            self.gap = 0;
+            self.complete = false;
+        }
+
+        let mut since_checkpoint = 0;
+        while self.gap < options.max_gap {
+            let id = ItemId::new(self.next_id);
+            match client.get_item_by_id(self.next_id).await {
+                Ok(item) => {
+                    self.items.insert(id, item);
+                    self.gap = 0;
+                }
+                Err(Error::Api {
+                    error: ApiErrorType::ItemNotFound,
+                    ..
+                }) => self.gap += 1,
+                Err(Error::JsonDecode { source, status, .. }) if status.is_success() => {
+                    log::warn!(target: "smmo_api", "skipping item {} in the catalog, it could not be decoded: {}", id, source);
+                    self.failed.insert(id, source.to_string());
+                    self.gap = 0;
+                }
+                Err(why) => {
+                    // the crawl error is the one worth returning; the checkpoint is best effort
+                    if let Err(checkpoint_error) = self.checkpoint(options) {
+                        log::error!(target: "smmo_api", "unable to checkpoint item catalog: {}", checkpoint_error);
+                    }
+                    return Err(CatalogError::Smmo(why));
+                }
+            }
+            self.next_id += 1;
+
+            since_checkpoint += 1;
+            if since_checkpoint >= options.checkpoint_every {
+                self.checkpoint(options)?;
+                since_checkpoint = 0;
+            }
+        }
+
+        self.complete = true;
+        self.checkpoint(options)
+    }
+
+    fn checkpoint(&self, options: &CrawlOptions) -> Result<(), CatalogError> {
+        match &options.checkpoint_path {
+            Some(path) => {
+                log::info!(target: "smmo_api", "checkpointing item catalog at id {}, {} items found", self.next_id, self.items.len());
+                self.save(path)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for ItemCatalog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How an [`ItemCatalog`] is crawled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrawlOptions {
+    max_gap: u32,
+    checkpoint_path: Option<PathBuf>,
+    checkpoint_every: u32,
+}
+
+impl CrawlOptions {
+    /// Stops after 100 missing ids in a row, without checkpointing.
+    pub fn new() -> Self {
+        Self {
+            max_gap: 100,
+            checkpoint_path: None,
+            checkpoint_every: 100,
+        }
+    }
+
+    /// How many ids in a row must be missing before they are taken to be the end of the items
+    /// rather than a gap.
+    pub fn max_gap(mut self, max_gap: u32) -> Self {
+        self.max_gap = max_gap.max(1);
+        self
+    }
+
+    /// Save the catalog to `path` every `every` ids, when an error stops the crawl, and when the
+    /// crawl finishes.
+    pub fn checkpoint(mut self, path: impl Into<PathBuf>, every: u32) -> Self {
+        self.checkpoint_path = Some(path.into());
+        self.checkpoint_every = every.max(1);
+        self
+    }
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+pub enum CatalogError {
+    /// Fetching an item failed.
+    Smmo(Error),
+    /// Reading or writing the catalog file failed.
+    Io(io::Error),
+    /// The catalog file could not be (de)serialized.
+    Json(serde_json::Error),
+}
+
+impl Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::Smmo(_) => f.write_str("unable to fetch an item for the catalog"),
+            CatalogError::Io(_) => f.write_str("unable to read or write the catalog file"),
+            CatalogError::Json(_) => f.write_str("the catalog file is not valid"),
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CatalogError::Smmo(why) => Some(why),
+            CatalogError::Io(why) => Some(why),
+            CatalogError::Json(why) => Some(why),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_item_catalog {
+    use super::*;
+    use crate::test_server::{MockResponse, TestServer};
+
+    fn item(id: u32) -> MockResponse {
+        MockResponse::json(&format!(
+            r#"{{
+                "id": {},
+                "name": "Item {}",
+                "type": "Material",
+                "description": "",
+                "equipable": "0",
+                "level": 1,
+                "rarity": "Common",
+                "value": 20,
+                "stat1": null,
+                "stat1modifier": 0,
+                "stat2": null,
+                "stat2modifier": 0,
+                "stat3": null,
+                "stat3modifier": 0,
+                "custom_item": 0,
+                "tradable": 1,
+                "locked": 0
+            }}"#,
+            id, id
+        ))
+    }
+
+    fn not_found() -> MockResponse {
+        MockResponse::json(r#"{ "error": "item not found" }"#)
+    }
+
+    fn client(server: &TestServer) -> SmmoClient {
+        SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .build()
+            .unwrap()
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("smmo-api-{}-{}.json", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[tokio::test]
+    async fn test_crawl_skips_gaps_and_stops_at_end() {
+        let server = TestServer::start(vec![
+            item(1),
+            not_found(),
+            item(3),
+            not_found(),
+            not_found(),
+        ])
+        .await;
+
+        let mut catalog = ItemCatalog::new();
+        catalog
+            .crawl(&client(&server), &CrawlOptions::new().max_gap(2))
+            .await
+            .unwrap();
+
+        assert!(catalog.is_complete());
+        assert_eq!(
+            catalog.items().keys().copied().collect::<Vec<_>>(),
+            vec![ItemId::new(1), ItemId::new(3)]
+        );
+        assert_eq!(server.requests().len(), 5);
+        assert_eq!(server.requests()[4].target, "/v1/item/info/5");
+    }
+
+    #[tokio::test]
+    async fn test_crawl_resumes_from_checkpoint() {
+        let path = temp_path("catalog-resume");
+        let server = TestServer::start(vec![
+            item(1),
+            item(2),
+            MockResponse::json("oops").status(500),
+        ])
+        .await;
+        let options = CrawlOptions::new().max_gap(1).checkpoint(&path, 10);
+
+        let mut catalog = ItemCatalog::load_or_new(&path).unwrap();
+        assert!(matches!(
+            catalog.crawl(&client(&server), &options).await,
+            Err(CatalogError::Smmo(_))
+        ));
+
+        let server = TestServer::start(vec![item(3), not_found()]).await;
+        let mut catalog = ItemCatalog::load_or_new(&path).unwrap();
+        assert_eq!(catalog.items().len(), 2);
+        assert!(!catalog.is_complete());
+        catalog.crawl(&client(&server), &options).await.unwrap();
+
+        assert_eq!(server.requests()[0].target, "/v1/item/info/3");
+        assert_eq!(catalog.items().len(), 3);
+        assert_eq!(ItemCatalog::load(&path).unwrap(), catalog);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_crawl_skips_items_that_cant_be_decoded() {
+        let path = temp_path("catalog-failed");
+        let server = TestServer::start(vec![
+            item(1),
+            MockResponse::json(r#"{ "id": 2 }"#),
+            item(3),
+            not_found(),
+        ])
+        .await;
+        let options = CrawlOptions::new().max_gap(1).checkpoint(&path, 10);
+
+        let mut catalog = ItemCatalog::new();
+        catalog.crawl(&client(&server), &options).await.unwrap();
+
+        assert!(catalog.is_complete());
+        assert_eq!(catalog.items().len(), 2);
+        assert_eq!(
+            catalog.failed().keys().copied().collect::<Vec<_>>(),
+            vec![ItemId::new(2)]
+        );
+        assert!(catalog.failed()[&ItemId::new(2)].contains("missing field"));
+        assert_eq!(ItemCatalog::load(&path).unwrap(), catalog);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_crawl_error_outlives_failed_checkpoint() {
+        let path = std::env::temp_dir()
+            .join(format!("smmo-api-no-such-dir-{}", std::process::id()))
+            .join("catalog.json");
+        let server = TestServer::start(vec![MockResponse::json("oops").status(500)]).await;
+        let options = CrawlOptions::new().checkpoint(&path, 10);
+
+        let mut catalog = ItemCatalog::new();
+        let error = catalog.crawl(&client(&server), &options).await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            CatalogError::Smmo(Error::JsonDecode { status, .. })
+                if status == reqwest::StatusCode::INTERNAL_SERVER_ERROR
+        ));
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_crawl_complete_catalog_looks_for_new_items() {
+        let server = TestServer::start(vec![item(1), not_found()]).await;
+        let options = CrawlOptions::new().max_gap(1);
+        let mut catalog = ItemCatalog::new();
+        catalog.crawl(&client(&server), &options).await.unwrap();
+
+        let server = TestServer::start(vec![item(2), not_found()]).await;
+        catalog.crawl(&client(&server), &options).await.unwrap();
+
+        assert_eq!(server.requests()[0].target, "/v1/item/info/2");
+        assert_eq!(catalog.items().len(), 2);
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let path = temp_path("catalog-missing");
+        assert_eq!(ItemCatalog::load_or_new(&path).unwrap(), ItemCatalog::new());
+        assert!(matches!(ItemCatalog::load(&path), Err(CatalogError::Io(_))));
+    }
+}
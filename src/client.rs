@@ -1,28 +1,88 @@
-use crate::models::{item::Item, orphanage::Orphanage, world_boss::WorldBosses};
-use serde::{Deserialize, Serialize};
+use crate::{
+    decode::{self, DecodeMode, DecodeWarnings},
+    error::{ApiErrorResponse, Error},
+    models::{item::Item, orphanage::Orphanage, world_boss::WorldBosses},
+    rate_limit::{RateLimit, RateLimitStats, RateLimiter},
+    redact::{self, ApiKey, API_KEY_PARAM},
+    retry::{self, RetryPolicy},
+};
+use bytes::Bytes;
+use futures::stream::{self, StreamExt};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, USER_AGENT},
+    StatusCode,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::time::Instant;
+
+pub use crate::error::ApiErrorType;
+pub use reqwest::Url;
 
 #[cfg(feature = "env")]
 use dotenv::dotenv;
 
 use std::{
-    error::Error,
-    fmt::{Debug, Display},
-    marker::PhantomData,
+    collections::{BTreeSet, HashMap},
+    fmt::{self, Debug, Display},
+    sync::Arc,
+    time::Duration,
 };
 
-use crate::models::{smmo_player::SmmoPlayer, SmmoModel};
+use crate::models::{
+    diamond_market::DiamondMarket,
+    guild::{Guild, GuildId, GuildMembers, GuildWars, WarStatus},
+    inventory::{Inventory, InventoryItem},
+    item::ItemId,
+    pet::PlayerPets,
+    player_equipment::PlayerEquipment,
+    player_skills::PlayerSkills,
+    smmo_player::{SelfPlayer, SmmoPlayer, UserId},
+    SmmoModel,
+};
+
+/// The base url of the official smmo api.
+pub const DEFAULT_BASE_URL: &str = "https://api.simple-mmo.com/v1/";
 
+/// A client for the smmo api.
+///
+/// The api key is never included in the `Debug` output of the client, nor in any url, error or
+/// log line produced by it.
+#[derive(Clone)]
 pub struct SmmoClient {
-    api_key: String,
+    api_key: ApiKey,
     inner: reqwest::Client,
+    base_url: Url,
+    timeout: Option<Duration>,
+    default_headers: HeaderMap,
+    api_key_placement: ApiKeyPlacement,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    retry_policy: RetryPolicy,
+    decode_mode: DecodeMode,
+    warnings_handler: Option<WarningsHandler>,
+}
+
+/// Where in the request the api key is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApiKeyPlacement {
+    #[default]
+    /// As a field of a `application/x-www-form-urlencoded` body, as the api documentation
+    /// describes. This keeps the key out of urls, and therefore out of proxy and server logs.
+    FormBody,
+    /// As a query parameter of a bodyless request. Only use this if the api (or whatever is
+    /// standing in for it) doesn't accept the key in the body.
+    Query,
 }
 
 impl SmmoClient {
     pub fn new(api_key: String) -> Self {
-        Self {
-            api_key,
-            inner: reqwest::Client::new(),
-        }
+        Self::builder(api_key)
+            .build()
+            .expect("the default client configuration is valid")
+    }
+
+    pub fn builder(api_key: String) -> SmmoClientBuilder {
+        SmmoClientBuilder::new(api_key)
     }
 
     #[cfg(feature = "env")]
@@ -33,189 +93,725 @@ impl SmmoClient {
         )
     }
 
+    /// The base url that all endpoints are resolved against.
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    pub fn decode_mode(&self) -> DecodeMode {
+        self.decode_mode
+    }
+
+    /// The rate limit shared by this client and all of its clones, if one was configured.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limiter.as_ref().map(|limiter| limiter.limit())
+    }
+
+    /// How long requests have waited for the rate limiter so far, across this client and all
+    /// of its clones.
+    pub fn rate_limit_stats(&self) -> Option<RateLimitStats> {
+        self.rate_limiter.as_ref().map(|limiter| limiter.stats())
+    }
+
     pub async fn get_player_by_smmo_id(&self, smmo_id: String) -> SmmoResult<SmmoPlayer> {
-        let url = format!("https://api.simple-mmo.com/v1/player/info/{}", smmo_id);
-        self.get_internal(&url).await
+        self.get_player_by_smmo_id_with_raw(smmo_id)
+            .await
+            .map(Response::into_inner)
     }
 
-    pub async fn get_world_bosses<'a, 'b>(&'a self) -> SmmoResult<WorldBosses> {
-        let url = "https://api.simple-mmo.com/v1/worldboss/all";
+    /// Like [`get_player_by_smmo_id`](Self::get_player_by_smmo_id), along with the raw response.
+    pub async fn get_player_by_smmo_id_with_raw(
+        &self,
+        smmo_id: String,
+    ) -> SmmoResult<Response<SmmoPlayer>> {
+        let url = self.endpoint(&["player", "info", &smmo_id]);
         self.get_internal(url).await
     }
 
-    pub async fn get_orphanage<'a, 'b>(&'a self) -> SmmoResult<Orphanage> {
-        let url = "https://api.simple-mmo.com/v1/orphanage";
+    /// The owner of the api key, including their private information.
+    pub async fn me(&self) -> SmmoResult<SelfPlayer> {
+        self.me_with_raw().await.map(Response::into_inner)
+    }
+
+    /// Like [`me`](Self::me), along with the raw response.
+    pub async fn me_with_raw(&self) -> SmmoResult<Response<SelfPlayer>> {
+        let url = self.endpoint(&["player", "me"]);
         self.get_internal(url).await
     }
 
-    pub async fn get_item_by_id<'a, 'b>(&'a self, id: u32) -> SmmoResult<Item> {
-        let url = format!("https://api.simple-mmo.com/v1/item/info/{}", id);
-        self.get_internal(&url).await
+    /// The items owned by the owner of the api key.
+    pub async fn get_inventory(&self) -> SmmoResult<Inventory> {
+        self.get_inventory_with_raw()
+            .await
+            .map(Response::into_inner)
     }
 
-    async fn get_internal<T: SmmoModel>(&self, url: &str) -> SmmoResult<T> {
-        match self
-            .inner
-            .post(&*url)
-            .query(&[("api_key", &*self.api_key)])
-            .send()
+    /// Like [`get_inventory`](Self::get_inventory), along with the raw response.
+    pub async fn get_inventory_with_raw(&self) -> SmmoResult<Response<Inventory>> {
+        let url = self.endpoint(&["player", "inventory"]);
+        self.get_internal(url).await
+    }
+
+    /// Fetches the full [`Item`] for every entry of the inventory, one at a time, stopping at the
+    /// first error.
+    pub async fn resolve_inventory(&self, inventory: &Inventory) -> SmmoResult<Vec<InventoryItem>> {
+        let mut items = Vec::with_capacity(inventory.0.len());
+        for entry in &inventory.0 {
+            let item = self.get_item_by_id(entry.item_id.inner()).await?;
+            items.push(InventoryItem {
+                entry: entry.clone(),
+                item,
+            });
+        }
+        Ok(items)
+    }
+
+    /// The items the player is currently wearing.
+    pub async fn get_player_equipment(&self, user_id: UserId) -> SmmoResult<PlayerEquipment> {
+        self.get_player_equipment_with_raw(user_id)
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Like [`get_player_equipment`](Self::get_player_equipment), along with the raw response.
+    pub async fn get_player_equipment_with_raw(
+        &self,
+        user_id: UserId,
+    ) -> SmmoResult<Response<PlayerEquipment>> {
+        let url = self.endpoint(&["player", "equipment", &user_id.to_string()]);
+        self.get_internal(url).await
+    }
+
+    /// The player's gathering and crafting skills.
+    pub async fn get_player_skills(&self, user_id: UserId) -> SmmoResult<PlayerSkills> {
+        self.get_player_skills_with_raw(user_id)
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Like [`get_player_skills`](Self::get_player_skills), along with the raw response.
+    pub async fn get_player_skills_with_raw(
+        &self,
+        user_id: UserId,
+    ) -> SmmoResult<Response<PlayerSkills>> {
+        let url = self.endpoint(&["player", "skills", &user_id.to_string()]);
+        self.get_internal(url).await
+    }
+
+    /// The pets the player owns.
+    pub async fn get_player_pets(&self, user_id: UserId) -> SmmoResult<PlayerPets> {
+        self.get_player_pets_with_raw(user_id)
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Like [`get_player_pets`](Self::get_player_pets), along with the raw response.
+    pub async fn get_player_pets_with_raw(
+        &self,
+        user_id: UserId,
+    ) -> SmmoResult<Response<PlayerPets>> {
+        let url = self.endpoint(&["player", "pets", &user_id.to_string()]);
+        self.get_internal(url).await
+    }
+
+    pub async fn get_guild(&self, guild_id: GuildId) -> SmmoResult<Guild> {
+        self.get_guild_with_raw(guild_id)
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Like [`get_guild`](Self::get_guild), along with the raw response.
+    pub async fn get_guild_with_raw(&self, guild_id: GuildId) -> SmmoResult<Response<Guild>> {
+        let url = self.endpoint(&["guilds", "info", &guild_id.to_string()]);
+        self.get_internal(url).await
+    }
+
+    pub async fn get_guild_members(&self, guild_id: GuildId) -> SmmoResult<GuildMembers> {
+        self.get_guild_members_with_raw(guild_id)
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Like [`get_guild_members`](Self::get_guild_members), along with the raw response.
+    pub async fn get_guild_members_with_raw(
+        &self,
+        guild_id: GuildId,
+    ) -> SmmoResult<Response<GuildMembers>> {
+        let url = self.endpoint(&["guilds", "members", &guild_id.to_string()]);
+        self.get_internal(url).await
+    }
+
+    /// The guild's wars with the given status.
+    pub async fn get_guild_wars(
+        &self,
+        guild_id: GuildId,
+        status: WarStatus,
+    ) -> SmmoResult<GuildWars> {
+        self.get_guild_wars_with_raw(guild_id, status)
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Like [`get_guild_wars`](Self::get_guild_wars), along with the raw response.
+    pub async fn get_guild_wars_with_raw(
+        &self,
+        guild_id: GuildId,
+        status: WarStatus,
+    ) -> SmmoResult<Response<GuildWars>> {
+        let url = self.endpoint(&[
+            "guilds",
+            "wars",
+            &guild_id.to_string(),
+            &status.as_u8().to_string(),
+        ]);
+        self.get_internal(url).await
+    }
+
+    pub async fn get_world_bosses(&self) -> SmmoResult<WorldBosses> {
+        self.get_world_bosses_with_raw()
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Like [`get_world_bosses`](Self::get_world_bosses), along with the raw response.
+    pub async fn get_world_bosses_with_raw(&self) -> SmmoResult<Response<WorldBosses>> {
+        let url = self.endpoint(&["worldboss", "all"]);
+        self.get_internal(url).await
+    }
+
+    pub async fn get_orphanage(&self) -> SmmoResult<Orphanage> {
+        self.get_orphanage_with_raw()
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Like [`get_orphanage`](Self::get_orphanage), along with the raw response.
+    pub async fn get_orphanage_with_raw(&self) -> SmmoResult<Response<Orphanage>> {
+        let url = self.endpoint(&["orphanage"]);
+        self.get_internal(url).await
+    }
+
+    /// The current listings on the diamond market.
+    pub async fn get_diamond_market(&self) -> SmmoResult<DiamondMarket> {
+        self.get_diamond_market_with_raw()
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Like [`get_diamond_market`](Self::get_diamond_market), along with the raw response.
+    pub async fn get_diamond_market_with_raw(&self) -> SmmoResult<Response<DiamondMarket>> {
+        let url = self.endpoint(&["diamond-market"]);
+        self.get_internal(url).await
+    }
+
+    pub async fn get_item_by_id(&self, id: u32) -> SmmoResult<Item> {
+        self.get_item_by_id_with_raw(id)
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Like [`get_item_by_id`](Self::get_item_by_id), along with the raw response.
+    pub async fn get_item_by_id_with_raw(&self, id: u32) -> SmmoResult<Response<Item>> {
+        let url = self.endpoint(&["item", "info", &id.to_string()]);
+        self.get_internal(url).await
+    }
+
+    /// Fetches many items, with at most `concurrency` requests in flight at once (at least one
+    /// is always allowed). Requests still go through the client's rate limiter and retry policy.
+    ///
+    /// Every id gets its own result, so a missing item doesn't stop the others from being
+    /// fetched. Duplicate ids are only fetched once.
+    pub async fn get_items<I: IntoIterator<Item = ItemId>>(
+        &self,
+        ids: I,
+        concurrency: usize,
+    ) -> HashMap<ItemId, SmmoResult<Item>> {
+        let ids = ids.into_iter().collect::<BTreeSet<_>>();
+        stream::iter(ids)
+            .map(|id| async move { (id, self.get_item_by_id(id.inner()).await) })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
             .await
-        {
-            Ok(res) => {
-                let req_url = res.url().to_string();
-                match res.text().await {
-                    Ok(text) => {
-                        let serde_result = serde_json::from_str::<InternalSmmoResult<T>>(&text);
-                        match serde_result {
-                            Ok(json) => json.into(),
-                            Err(why) => {
-                                log::error!(target: "smmo_api", "url: {}, error: {}", url, why.to_string());
-                                Err(SmmoError::JsonDecodeError(text, req_url))
-                            }
-                        }
+    }
+
+    /// Appends the given path segments to the base url.
+    fn endpoint(&self, segments: &[&str]) -> Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base url is checked to be a valid base when building the client")
+            .pop_if_empty()
+            .extend(segments);
+        url
+    }
+
+    async fn get_internal<T: SmmoModel>(&self, url: Url) -> SmmoResult<Response<T>> {
+        let mut attempt = 1;
+        loop {
+            let response = self.fetch(&url).await;
+
+            if attempt < self.retry_policy.max_attempts() {
+                if let Some(delay) = self.retry_delay(&response, attempt) {
+                    log::warn!(target: "smmo_api", "url: {}, attempt {} failed, retrying in {:?}", url, attempt, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                    continue;
+                }
+            }
+
+            return match response {
+                Ok(raw) => match decode_body::<T>(&raw.body, self.decode_mode) {
+                    Ok(InternalSmmoResult::Ok((value, warnings))) => {
+                        self.report_warnings(&raw.url, &warnings);
+                        Ok(Response {
+                            value,
+                            raw,
+                            attempts: attempt,
+                            warnings,
+                        })
                     }
+                    Ok(InternalSmmoResult::Err(err)) => Err(Error::Api {
+                        error: err.error,
+                        expected: T::TYPE_NAME,
+                        status: raw.status,
+                        headers: Box::new(raw.headers),
+                    }),
                     Err(why) => {
-                        log::error!(target: "smmo_api", "url: {}, error: {}", url, why.to_string());
-                        Err(SmmoError::ReqwestError(why))
+                        log::error!(target: "smmo_api", "url: {}, error: {}", url, why);
+                        Err(Error::JsonDecode {
+                            source: why,
+                            expected: T::TYPE_NAME,
+                            url: raw.url.to_string(),
+                            status: raw.status,
+                            headers: Box::new(raw.headers),
+                            body: raw.body.to_vec(),
+                        })
                     }
+                },
+                Err(why) => {
+                    log::error!(target: "smmo_api", "url: {}, error: {}", url, why);
+                    Err(Error::Http {
+                        source: why,
+                        expected: T::TYPE_NAME,
+                    })
                 }
+            };
+        }
+    }
+
+    fn report_warnings(&self, url: &Url, warnings: &DecodeWarnings) {
+        if warnings.is_empty() {
+            return;
+        }
+        for warning in warnings {
+            log::warn!(target: "smmo_api", "url: {}, {}", url, warning);
+        }
+        if let Some(handler) = &self.warnings_handler {
+            (handler.0)(url, warnings);
+        }
+    }
+
+    /// Makes a single request, waiting for the rate limiter first if there is one.
+    async fn fetch(&self, url: &Url) -> Result<RawResponse, reqwest::Error> {
+        let mut rate_limit_wait = Duration::from_secs(0);
+        if let Some(limiter) = &self.rate_limiter {
+            rate_limit_wait = limiter.acquire().await;
+            if rate_limit_wait > Duration::from_secs(0) {
+                log::debug!(target: "smmo_api", "url: {}, waited {:?} for the rate limiter", url, rate_limit_wait);
             }
-            Err(why) => {
-                log::error!(target: "smmo_api", "url: {}, error: {}", url, why.to_string());
-                Err(SmmoError::InternalError)
+        }
+
+        let mut request = self
+            .inner
+            .post(url.clone())
+            .headers(self.default_headers.clone());
+        let api_key = [(API_KEY_PARAM, self.api_key.expose())];
+        request = match self.api_key_placement {
+            ApiKeyPlacement::FormBody => request.form(&api_key),
+            ApiKeyPlacement::Query => request.query(&api_key),
+        };
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+
+        let sent = Instant::now();
+        let res = request.send().await.map_err(redact::redact_error)?;
+        let status = res.status();
+        let headers = res.headers().clone();
+        let url = redact::redact_url(res.url());
+        let body = res.bytes().await.map_err(redact::redact_error)?;
+        Ok(RawResponse {
+            status,
+            headers,
+            url,
+            body,
+            latency: sent.elapsed(),
+            rate_limit_wait,
+        })
+    }
+
+    /// How long to wait before retrying, or `None` if the request shouldn't be retried.
+    fn retry_delay(
+        &self,
+        response: &Result<RawResponse, reqwest::Error>,
+        attempt: u32,
+    ) -> Option<Duration> {
+        match response {
+            Ok(raw) => {
+                if !retry::is_retryable_status(raw.status) {
+                    return None;
+                }
+                // checked on its own, since some models (such as `PlayerEquipment`) would also
+                // accept the error body
+                if let Ok(ApiErrorResponse {
+                    error: ApiErrorType::Unauthenticated,
+                }) = serde_json::from_slice(&raw.body)
+                {
+                    return None;
+                }
+                Some(self.retry_policy.delay(&raw.headers, attempt))
             }
+            Err(why) if retry::is_retryable_error(why) => Some(self.retry_policy.backoff(attempt)),
+            Err(_) => None,
         }
     }
 }
 
-pub type SmmoResult<T> = Result<T, SmmoError<T>>;
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(untagged)]
-enum InternalSmmoResult<T> {
-    Ok(T),
-    Err(SmmoError<T>),
+impl Debug for SmmoClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmmoClient")
+            .field("api_key", &self.api_key)
+            .field("base_url", &self.base_url)
+            .field("timeout", &self.timeout)
+            .field("default_headers", &self.default_headers)
+            .field("api_key_placement", &self.api_key_placement)
+            .field("rate_limit", &self.rate_limit())
+            .field("retry_policy", &self.retry_policy)
+            .field("decode_mode", &self.decode_mode)
+            .finish()
+    }
 }
 
-impl<T: SmmoModel> From<InternalSmmoResult<T>> for Result<T, SmmoError<T>> {
-    fn from(val: InternalSmmoResult<T>) -> Self {
-        match val {
-            InternalSmmoResult::Ok(ok) => Ok(ok),
-            InternalSmmoResult::Err(err) => Err(err),
-        }
+/// See [`SmmoClientBuilder::on_decode_warnings`].
+#[derive(Clone)]
+struct WarningsHandler(Arc<WarningsHandlerFn>);
+
+type WarningsHandlerFn = dyn Fn(&Url, &DecodeWarnings) + Send + Sync;
+
+impl Debug for WarningsHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WarningsHandler")
     }
 }
 
-// impl<'s, T: SmmoModel<'s> + Debug> Error for SmmoResult<'s, T> {}
-
-// impl<'s, T: SmmoModel<'s> + Display> Display for SmmoResult<'s, T> {
-//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-//         let string = match self {
-//             SmmoResult::Ok(t) => t.to_string(),
-//             Err(err) => err.to_string(),
-//         };
-//         f.write_str(&string)
-//     }
-// }
+/// A response that has been read, but not yet decoded. The url has the api key redacted.
+#[derive(Debug)]
+struct RawResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    url: Url,
+    body: Bytes,
+    /// From sending the request until the whole body was read.
+    latency: Duration,
+    /// How long the request waited for the rate limiter before being sent.
+    rate_limit_wait: Duration,
+}
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum SmmoError<T /* : SmmoModel<'s> */> {
-    // #[serde(rename = "error")]
-    ApiError {
-        error: ApiErrorType,
-    },
-    /// Something went wrong internally, check the logs.
-    #[serde(skip)]
-    InternalError,
-    /// Unable to deserialize the api response; most likely means that the response structure changed.
-    #[serde(skip)]
-    JsonDecodeError(String, String),
-    /// Something went wrong when fetching from the smmo api.
-    #[serde(skip)]
-    ReqwestError(reqwest::Error),
-    /// Used to appease the typechecker. should never be constructed.
-    #[serde(skip)]
-    PhantomData(PhantomData<T>),
+/// A decoded model together with the response it was decoded from, as returned by the
+/// `*_with_raw` methods of [`SmmoClient`]. If the request was retried, this is the response to
+/// the last attempt.
+#[derive(Debug)]
+pub struct Response<T> {
+    value: T,
+    raw: RawResponse,
+    attempts: u32,
+    warnings: DecodeWarnings,
 }
 
-impl<T> SmmoError<T> {
-    // pub(crate) fn unauthenticated
+impl<T> Response<T> {
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.raw.status
+    }
+
+    /// All of the response headers, including any rate limit headers the api sent.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.raw.headers
+    }
+
+    /// The url the response came from, with the api key redacted.
+    pub fn url(&self) -> &Url {
+        &self.raw.url
+    }
+
+    /// The body of the response, exactly as it was received.
+    pub fn body(&self) -> &[u8] {
+        &self.raw.body
+    }
+
+    /// The body of the response as text, or `None` if it isn't valid UTF-8.
+    pub fn text(&self) -> Option<&str> {
+        std::str::from_utf8(&self.raw.body).ok()
+    }
+
+    /// How long the request took, from sending it until the whole body was read. Time spent
+    /// waiting for the rate limiter is not included; see
+    /// [`rate_limit_wait`](Self::rate_limit_wait).
+    pub fn latency(&self) -> Duration {
+        self.raw.latency
+    }
+
+    /// How long the request waited for the client's rate limiter before being sent.
+    pub fn rate_limit_wait(&self) -> Duration {
+        self.raw.rate_limit_wait
+    }
+
+    /// How many times the request was made, counting retries.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Anything unexpected in the body. These have already been logged and passed to the
+    /// client's [`on_decode_warnings`](SmmoClientBuilder::on_decode_warnings) handler.
+    pub fn warnings(&self) -> &DecodeWarnings {
+        &self.warnings
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
-pub enum ApiErrorType {
-    /// Error from the api; means the item was not found.
-    /// TODO: Include the invalid item id in the variant.
-    #[serde(alias = "item not found")]
-    ItemNotFound,
-    /// Error from the api; means the api_key is not valid.
-    #[serde(alias = "unauthenticated")]
-    Unauthenticated,
+/// Configures and builds a [`SmmoClient`].
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use smmo_api::client::{SmmoClient, Url};
+/// let client = SmmoClient::builder("api key".into())
+///     .base_url(Url::parse("http://localhost:8080/v1/").unwrap())
+///     .timeout(Duration::from_secs(10))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SmmoClientBuilder {
+    api_key: ApiKey,
+    base_url: Option<Url>,
+    http_client: Option<reqwest::Client>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    default_headers: HeaderMap,
+    api_key_placement: ApiKeyPlacement,
+    rate_limit: Option<RateLimit>,
+    retry_policy: RetryPolicy,
+    decode_mode: DecodeMode,
+    warnings_handler: Option<WarningsHandler>,
 }
 
-impl<'s, T: SmmoModel> Display for SmmoError<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!(
-            "{}",
-            match self {
-                SmmoError::InternalError => "Something went wrong! Check the logs!".into(),
-                SmmoError::JsonDecodeError(original, url) => format!(
-                    r#"JSON decode error.
-URL: `{}`
-JSON recieved from the api: ```{}```
-Expected type: {}
-"#,
-                    url,
-                    original,
-                    T::TYPE_NAME
-                ),
-                SmmoError::ReqwestError(error) =>
-                    format!("Error with the SMMO api: ```{}```", error),
-                SmmoError::PhantomData(_) => {
-                    log::error!("PhantomData variant should never be constructed.");
-                    unsafe { std::hint::unreachable_unchecked() }
+impl SmmoClientBuilder {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            api_key: ApiKey::new(api_key),
+            base_url: None,
+            http_client: None,
+            timeout: None,
+            connect_timeout: None,
+            default_headers: HeaderMap::new(),
+            api_key_placement: ApiKeyPlacement::default(),
+            rate_limit: None,
+            retry_policy: RetryPolicy::never(),
+            decode_mode: DecodeMode::default(),
+            warnings_handler: None,
+        }
+    }
+
+    /// The url that all endpoints are resolved against. Defaults to [`DEFAULT_BASE_URL`].
+    ///
+    /// Endpoint paths are appended to the path of this url, so `http://localhost:8080/v1` and
+    /// `http://localhost:8080/v1/` are equivalent.
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Use a pre-configured [`reqwest::Client`] instead of building a new one.
+    ///
+    /// The [`timeout`](Self::timeout), [`user_agent`](Self::user_agent) and
+    /// [`default_headers`](Self::default_headers) are still applied to every request, but the
+    /// [`connect_timeout`](Self::connect_timeout) is ignored since it can only be set on the
+    /// client itself.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Timeout for each request, from when it starts connecting until the response body has
+    /// finished.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for only the connect phase of each request.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// The `User-Agent` header sent with every request.
+    pub fn user_agent(mut self, user_agent: HeaderValue) -> Self {
+        self.default_headers.insert(USER_AGENT, user_agent);
+        self
+    }
+
+    /// Headers sent with every request. These are merged with any headers set previously,
+    /// replacing those with the same name.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        for (name, value) in headers {
+            if let Some(name) = name {
+                self.default_headers.insert(name, value);
+            }
+        }
+        self
+    }
+
+    /// Where in each request the api key is sent. Defaults to [`ApiKeyPlacement::FormBody`].
+    pub fn api_key_placement(mut self, api_key_placement: ApiKeyPlacement) -> Self {
+        self.api_key_placement = api_key_placement;
+        self
+    }
+
+    /// Pace requests to stay under the given quota. Requests over the quota wait until they
+    /// can be sent, in the order they were made. The limiter is shared between the built client
+    /// and all of its clones.
+    ///
+    /// No rate limiting is done by default.
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Retry requests that failed for transient reasons. See [`RetryPolicy`] for what is
+    /// retried and how.
+    ///
+    /// Requests are not retried by default.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// How strictly responses are decoded. Defaults to [`DecodeMode::Lenient`].
+    pub fn decode_mode(mut self, decode_mode: DecodeMode) -> Self {
+        self.decode_mode = decode_mode;
+        self
+    }
+
+    /// Called with the url and the [`DecodeWarnings`] of every response that didn't exactly
+    /// match its model, in addition to each warning being logged. Only lenient decoding produces
+    /// warnings; in strict mode they are errors instead.
+    pub fn on_decode_warnings(
+        mut self,
+        handler: impl Fn(&Url, &DecodeWarnings) + Send + Sync + 'static,
+    ) -> Self {
+        self.warnings_handler = Some(WarningsHandler(Arc::new(handler)));
+        self
+    }
+
+    pub fn build(self) -> Result<SmmoClient, BuildError> {
+        let base_url = match self.base_url {
+            Some(url) if url.cannot_be_a_base() => return Err(BuildError::InvalidBaseUrl(url)),
+            Some(url) => url,
+            None => Url::parse(DEFAULT_BASE_URL).expect("default base url is valid"),
+        };
+
+        let inner = match self.http_client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
                 }
-                // SmmoError::ItemNotFound => "Item not found".to_string(),
-                SmmoError::ApiError { .. } => {
-                    todo!()
-                } // SmmoError::Unauthenticated => {
-                  //     "Authentication error with the SMMO api. Check the api key.".into()
-                  // }
-            },
-        ))
+                builder.build().map_err(BuildError::Reqwest)?
+            }
+        };
+
+        Ok(SmmoClient {
+            api_key: self.api_key,
+            inner,
+            base_url,
+            timeout: self.timeout,
+            default_headers: self.default_headers,
+            api_key_placement: self.api_key_placement,
+            rate_limiter: self
+                .rate_limit
+                .map(|limit| Arc::new(RateLimiter::new(limit))),
+            retry_policy: self.retry_policy,
+            decode_mode: self.decode_mode,
+            warnings_handler: self.warnings_handler,
+        })
     }
 }
 
-impl<'s, T: SmmoModel + Debug> Error for SmmoError<T> {}
-
-// #[cfg(try_trait)]
-// #[feature(try_trait)]
+/// An error that occurred while building a [`SmmoClient`].
+#[derive(Debug)]
+pub enum BuildError {
+    /// The base url can't have paths appended to it, e.g. a `data:` or `mailto:` url.
+    InvalidBaseUrl(Url),
+    /// The underlying http client could not be built.
+    Reqwest(reqwest::Error),
+}
 
-// impl<'s, T: SmmoModel<'s>> Try for SmmoResult<'s, T> {
-//     type Ok = T;
+impl Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::InvalidBaseUrl(url) => write!(f, "`{}` is not a valid base url", url),
+            BuildError::Reqwest(error) => write!(f, "unable to build the http client: {}", error),
+        }
+    }
+}
 
-//     type Error = SmmoError<'s, T>;
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::InvalidBaseUrl(_) => None,
+            BuildError::Reqwest(error) => Some(error),
+        }
+    }
+}
 
-//     fn into_result(self) -> Result<<SmmoResult<T> as Try>::Ok, Self::Error> {
-//         match self {
-//             SmmoResult::Ok(ok) => Ok(ok),
-//             Err(err) => Err(err),
-//         }
-//     }
+pub type SmmoResult<T> = Result<T, Error>;
 
-//     fn from_error(v: Self::Error) -> Self {
-//         Err(v)
-//     }
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum InternalSmmoResult<T> {
+    Ok(T),
+    Err(ApiErrorResponse),
+}
 
-//     fn from_ok(v: <SmmoResult<T> as Try>::Ok) -> Self {
-//         SmmoResult::Ok(v)
-//     }
-// }
+/// Decodes a response body, which is either a `T` or an api error. The api error is checked for
+/// first, since `T` can't be decoded through an untagged enum without losing track of where in
+/// the json any warnings are.
+fn decode_body<T: SmmoModel>(
+    body: &[u8],
+    mode: DecodeMode,
+) -> Result<InternalSmmoResult<(T, DecodeWarnings)>, serde_json::Error> {
+    let value = serde_json::from_slice::<Value>(body)?;
+    if let Ok(err) = ApiErrorResponse::deserialize(&value) {
+        return Ok(InternalSmmoResult::Err(err));
+    }
+    decode::decode_value(value, mode).map(InternalSmmoResult::Ok)
+}
 
 #[cfg(test)]
 mod test_internal_smmo_result_deserialize {
     use super::*;
+    use crate::test_fixtures::ITEM;
 
     #[test]
     fn test_item_not_found() {
@@ -225,7 +821,7 @@ mod test_internal_smmo_result_deserialize {
 
         assert!(matches!(
             serde_json::from_str::<InternalSmmoResult<Orphanage>>(json).unwrap(),
-            InternalSmmoResult::Err(SmmoError::ApiError {
+            InternalSmmoResult::Err(ApiErrorResponse {
                 error: ApiErrorType::ItemNotFound
             }),
         ));
@@ -238,8 +834,8 @@ mod test_internal_smmo_result_deserialize {
         }"#;
 
         assert!(matches!(
-            serde_json::from_str::<SmmoError<Orphanage>>(json).unwrap(),
-            SmmoError::ApiError {
+            serde_json::from_str::<ApiErrorResponse>(json).unwrap(),
+            ApiErrorResponse {
                 error: ApiErrorType::ItemNotFound
             },
         ));
@@ -262,7 +858,7 @@ mod test_internal_smmo_result_deserialize {
 
         assert!(matches!(
             serde_json::from_str::<InternalSmmoResult<Orphanage>>(json).unwrap(),
-            InternalSmmoResult::Err(SmmoError::ApiError {
+            InternalSmmoResult::Err(ApiErrorResponse {
                 error: ApiErrorType::Unauthenticated
             }),
         ));
@@ -275,8 +871,8 @@ mod test_internal_smmo_result_deserialize {
         }"#;
 
         assert!(matches!(
-            serde_json::from_str::<SmmoError<Orphanage>>(json).unwrap(),
-            SmmoError::ApiError {
+            serde_json::from_str::<ApiErrorResponse>(json).unwrap(),
+            ApiErrorResponse {
                 error: ApiErrorType::Unauthenticated
             },
         ));
@@ -293,27 +889,8 @@ mod test_internal_smmo_result_deserialize {
 
     #[test]
     fn test_item() {
-        let json = r#"{
-            "id": 1,
-            "name": "Wooden Stick",
-            "type": "Weapon",
-            "description": "",
-            "equipable": "1",
-            "level": 1,
-            "rarity": "Common",
-            "value": 20,
-            "stat1": "str",
-            "stat1modifier": 1,
-            "stat2": null,
-            "stat2modifier": 0,
-            "stat3": null,
-            "stat3modifier": null,
-            "custom_item": 0,
-            "tradable": 1,
-            "locked": 0
-        }"#;
         // assert_eq!(
-        serde_json::from_str::<InternalSmmoResult<Item>>(json).unwrap();
+        serde_json::from_str::<InternalSmmoResult<Item>>(ITEM).unwrap();
         //     ,InternalSmmoResult::Ok(Item {
         //         id: ItemId(1),
         //         name: "Wooden Stick".to_string(),
@@ -336,3 +913,530 @@ mod test_internal_smmo_result_deserialize {
         // )
     }
 }
+
+#[cfg(test)]
+mod test_smmo_client_builder {
+    use super::*;
+    use crate::{
+        test_fixtures::ORPHANAGE,
+        test_server::{MockResponse, TestServer},
+    };
+
+    #[test]
+    fn test_default_base_url() {
+        let client = SmmoClient::new("key".into());
+        assert_eq!(client.base_url().as_str(), DEFAULT_BASE_URL);
+        assert_eq!(
+            client.endpoint(&["item", "info", "1"]).as_str(),
+            "https://api.simple-mmo.com/v1/item/info/1"
+        );
+    }
+
+    #[test]
+    fn test_base_url_without_trailing_slash() {
+        let client = SmmoClient::builder("key".into())
+            .base_url(Url::parse("http://localhost:8080/v1").unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.endpoint(&["worldboss", "all"]).as_str(),
+            "http://localhost:8080/v1/worldboss/all"
+        );
+    }
+
+    #[test]
+    fn test_invalid_base_url() {
+        assert!(matches!(
+            SmmoClient::builder("key".into())
+                .base_url(Url::parse("mailto:someone@example.com").unwrap())
+                .build(),
+            Err(BuildError::InvalidBaseUrl(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_to_base_url() {
+        let server = TestServer::start(vec![MockResponse::json(ORPHANAGE)]).await;
+        let client = SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .user_agent(HeaderValue::from_static("smmo-api-tests"))
+            .build()
+            .unwrap();
+
+        let orphanage = client.get_orphanage().await.unwrap();
+        assert_eq!(orphanage.current_amount, 10);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert!(requests[0].target.starts_with("/v1/orphanage"));
+        assert_eq!(requests[0].header("user-agent"), Some("smmo-api-tests"));
+    }
+
+    #[tokio::test]
+    async fn test_custom_http_client_gets_default_headers() {
+        let server = TestServer::start(vec![MockResponse::json(ORPHANAGE)]).await;
+        let mut headers = HeaderMap::new();
+        headers.insert("x-test", HeaderValue::from_static("yes"));
+        let client = SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .http_client(reqwest::Client::new())
+            .default_headers(headers)
+            .build()
+            .unwrap();
+
+        client.get_orphanage().await.unwrap();
+
+        assert_eq!(server.requests()[0].header("x-test"), Some("yes"));
+    }
+}
+
+#[cfg(test)]
+mod test_endpoints {
+    use super::*;
+    use crate::{
+        test_fixtures::ITEM,
+        test_server::{MockResponse, TestServer},
+    };
+
+    #[tokio::test]
+    async fn test_get_player_equipment() {
+        let server = TestServer::start(vec![MockResponse::json(r#"{ "weapon": null }"#)]).await;
+        let client = SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let equipment = client.get_player_equipment(UserId::new(42)).await.unwrap();
+
+        assert_eq!(equipment.items().count(), 0);
+        assert_eq!(server.requests()[0].target, "/v1/player/equipment/42");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_inventory() {
+        let server = TestServer::start(vec![
+            MockResponse::json(r#"[{ "item_id": 1, "quantity": 3, "equipped": 0, "locked": 0 }]"#),
+            MockResponse::json(ITEM),
+        ])
+        .await;
+        let client = SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let inventory = client.get_inventory().await.unwrap();
+        let items = client.resolve_inventory(&inventory).await.unwrap();
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item.name, "Wooden Stick");
+        assert_eq!(items[0].value(), 60);
+        assert_eq!(crate::models::inventory::total_value(&items), 60);
+        let requests = server.requests();
+        assert_eq!(requests[0].target, "/v1/player/inventory");
+        assert_eq!(requests[1].target, "/v1/item/info/1");
+    }
+}
+
+#[cfg(test)]
+mod test_get_items {
+    use super::*;
+    use crate::{
+        test_fixtures::ITEM,
+        test_server::{MockResponse, TestServer},
+    };
+
+    #[tokio::test]
+    async fn test_get_items_keeps_going_after_errors() {
+        let server = TestServer::start(vec![
+            MockResponse::json(ITEM),
+            MockResponse::json(r#"{ "error": "item not found" }"#),
+            MockResponse::json(ITEM),
+        ])
+        .await;
+        let client = SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let ids = vec![1, 2, 3, 1].into_iter().map(ItemId::new);
+        let items = client.get_items(ids, 1).await;
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            items[&ItemId::new(1)].as_ref().unwrap().name,
+            "Wooden Stick"
+        );
+        assert_eq!(
+            items[&ItemId::new(2)].as_ref().unwrap_err().api_error(),
+            Some(&ApiErrorType::ItemNotFound)
+        );
+        assert!(items[&ItemId::new(3)].is_ok());
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_items_concurrently() {
+        let server = TestServer::start(vec![MockResponse::json(ITEM)]).await;
+        let client = SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let items = client.get_items((1..=20).map(ItemId::new), 4).await;
+
+        assert_eq!(items.len(), 20);
+        assert!(items.values().all(|item| item.is_ok()));
+        assert_eq!(server.requests().len(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_get_items_bounds_requests_in_flight() {
+        let server = TestServer::start(vec![
+            MockResponse::json(ITEM).delay(Duration::from_millis(50))
+        ])
+        .await;
+        let client = SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let items = client.get_items((1..=12).map(ItemId::new), 3).await;
+
+        assert!(items.values().all(|item| item.is_ok()));
+        assert!(server.max_in_flight() <= 3);
+        assert!(server.max_in_flight() > 1);
+    }
+}
+
+#[cfg(test)]
+mod test_api_key_placement {
+    use super::*;
+    use crate::{
+        test_fixtures::ORPHANAGE,
+        test_server::{MockResponse, TestServer},
+    };
+
+    #[tokio::test]
+    async fn test_api_key_sent_in_form_body() {
+        let server = TestServer::start(vec![MockResponse::json(ORPHANAGE)]).await;
+        let client = SmmoClient::builder("secret-key".into())
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        client.get_orphanage().await.unwrap();
+
+        let request = &server.requests()[0];
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.target, "/v1/orphanage");
+        assert_eq!(
+            request.header("content-type"),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(request.body, "api_key=secret-key");
+    }
+
+    #[tokio::test]
+    async fn test_api_key_sent_in_query() {
+        let server = TestServer::start(vec![MockResponse::json(ORPHANAGE)]).await;
+        let client = SmmoClient::builder("secret-key".into())
+            .base_url(server.url())
+            .api_key_placement(ApiKeyPlacement::Query)
+            .build()
+            .unwrap();
+
+        client.get_orphanage().await.unwrap();
+
+        let request = &server.requests()[0];
+        assert_eq!(request.target, "/v1/orphanage?api_key=secret-key");
+        assert_eq!(request.header("content-type"), None);
+        assert_eq!(request.body, "");
+    }
+
+    #[tokio::test]
+    async fn test_api_key_is_form_encoded() {
+        let server = TestServer::start(vec![MockResponse::json(ORPHANAGE)]).await;
+        let client = SmmoClient::builder("a&b=c d".into())
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        client.get_orphanage().await.unwrap();
+
+        assert_eq!(server.requests()[0].body, "api_key=a%26b%3Dc+d");
+    }
+}
+
+#[cfg(test)]
+mod test_rate_limit {
+    use super::*;
+    use crate::{
+        test_fixtures::ORPHANAGE,
+        test_server::{MockResponse, TestServer},
+    };
+
+    #[tokio::test]
+    async fn test_rate_limiter_is_shared_between_clones() {
+        let server = TestServer::start(vec![MockResponse::json(ORPHANAGE)]).await;
+        let client = SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .rate_limit(RateLimit::per_minute(10))
+            .build()
+            .unwrap();
+        let clone = client.clone();
+
+        client.get_orphanage().await.unwrap();
+        clone.get_orphanage().await.unwrap();
+
+        assert_eq!(client.rate_limit(), Some(RateLimit::per_minute(10)));
+        assert_eq!(client.rate_limit_stats().unwrap().requests, 2);
+        assert_eq!(clone.rate_limit_stats().unwrap().requests, 2);
+    }
+}
+
+#[cfg(test)]
+mod test_redaction {
+    use super::*;
+    use crate::test_server::{MockResponse, TestServer};
+
+    #[test]
+    fn test_debug_redacts_api_key() {
+        let client = SmmoClient::new("secret-key".into());
+        assert!(!format!("{:?}", client).contains("secret-key"));
+        assert!(!format!("{:?}", SmmoClient::builder("secret-key".into())).contains("secret-key"));
+    }
+
+    #[tokio::test]
+    async fn test_json_decode_error_redacts_api_key() {
+        let server = TestServer::start(vec![MockResponse::json("not json")]).await;
+        let client = SmmoClient::builder("secret-key".into())
+            .base_url(server.url())
+            .api_key_placement(ApiKeyPlacement::Query)
+            .build()
+            .unwrap();
+
+        let error = client.get_orphanage().await.unwrap_err();
+        assert!(matches!(error, Error::JsonDecode { .. }));
+        assert!(!error.to_string().contains("secret-key"));
+        assert!(!format!("{:?}", error).contains("secret-key"));
+    }
+
+    #[tokio::test]
+    async fn test_http_error_redacts_api_key() {
+        // bind then drop a listener to get a port that nothing is listening on.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/v1/", listener.local_addr().unwrap())).unwrap();
+        drop(listener);
+        let client = SmmoClient::builder("secret-key".into())
+            .base_url(url)
+            .api_key_placement(ApiKeyPlacement::Query)
+            .build()
+            .unwrap();
+
+        let error = client.get_orphanage().await.unwrap_err();
+        assert!(matches!(error, Error::Http { .. }));
+        let source = std::error::Error::source(&error).unwrap();
+        assert!(!source.to_string().contains("secret-key"));
+        assert!(!format!("{:?}", error).contains("secret-key"));
+    }
+}
+
+#[cfg(test)]
+mod test_retries {
+    use super::*;
+    use crate::{
+        test_fixtures::ORPHANAGE,
+        test_server::{MockResponse, TestServer},
+    };
+
+    fn retrying_client(server: &TestServer) -> SmmoClient {
+        SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .retry_policy(RetryPolicy::new(3).initial_backoff(Duration::from_millis(1)))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let server = TestServer::start(vec![
+            MockResponse::json("oops").status(500),
+            MockResponse::json("")
+                .status(429)
+                .header("Retry-After", "0"),
+            MockResponse::json(ORPHANAGE),
+        ])
+        .await;
+
+        retrying_client(&server).get_orphanage().await.unwrap();
+
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let server = TestServer::start(vec![MockResponse::json("oops")
+            .status(503)
+            .header("X-Backend", "down")])
+        .await;
+
+        let error = retrying_client(&server).get_orphanage().await.unwrap_err();
+        assert!(matches!(
+            error,
+            Error::JsonDecode {
+                expected: "Orphanage",
+                ..
+            }
+        ));
+        assert_eq!(error.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert_eq!(error.headers().unwrap().get("x-backend").unwrap(), "down");
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_unauthenticated() {
+        let server = TestServer::start(vec![MockResponse::json(
+            r#"{ "error": "unauthenticated" }"#,
+        )
+        .status(503)])
+        .await;
+
+        let error = retrying_client(&server).get_orphanage().await.unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Api {
+                error: ApiErrorType::Unauthenticated,
+                expected: "Orphanage",
+                ..
+            }
+        ));
+        assert_eq!(error.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_unauthenticated_for_defaulted_models() {
+        let server = TestServer::start(vec![MockResponse::json(
+            r#"{ "error": "unauthenticated" }"#,
+        )
+        .status(503)])
+        .await;
+
+        assert!(matches!(
+            retrying_client(&server)
+                .get_player_equipment(UserId::new(42))
+                .await,
+            Err(Error::Api {
+                error: ApiErrorType::Unauthenticated,
+                expected: "PlayerEquipment",
+                ..
+            })
+        ));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_by_default() {
+        let server = TestServer::start(vec![MockResponse::json("oops").status(500)]).await;
+        let client = SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        assert!(client.get_orphanage().await.is_err());
+        assert_eq!(server.requests().len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod test_with_raw {
+    use super::*;
+    use crate::{
+        test_fixtures::ORPHANAGE,
+        test_server::{MockResponse, TestServer},
+    };
+
+    #[tokio::test]
+    async fn test_with_raw_keeps_the_response() {
+        let server = TestServer::start(vec![
+            MockResponse::json("oops").status(500),
+            MockResponse::json(ORPHANAGE).header("X-RateLimit-Remaining", "39"),
+        ])
+        .await;
+
+        let client = SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .retry_policy(RetryPolicy::new(3).initial_backoff(Duration::from_millis(1)))
+            .build()
+            .unwrap();
+
+        let response = client.get_orphanage_with_raw().await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), ORPHANAGE.as_bytes());
+        assert_eq!(response.text(), Some(ORPHANAGE));
+        assert_eq!(
+            response.headers().get("x-ratelimit-remaining").unwrap(),
+            "39"
+        );
+        assert!(!response.url().as_str().contains("key"));
+        assert_eq!(response.attempts(), 2);
+        assert_eq!(response.rate_limit_wait(), Duration::from_secs(0));
+        assert!(response.warnings().is_empty());
+        assert_eq!(response.into_inner().current_amount, 10);
+    }
+}
+
+#[cfg(test)]
+mod test_decode_mode {
+    use super::*;
+    use crate::{
+        models::item::ItemStat,
+        test_fixtures::ITEM,
+        test_server::{MockResponse, TestServer},
+    };
+
+    #[tokio::test]
+    async fn test_lenient_decode_reports_warnings() {
+        let server = TestServer::start(vec![MockResponse::json(
+            &ITEM.replace(r#""stat1": "str""#, r#""stat1": "luck""#),
+        )])
+        .await;
+        let reported = Arc::new(std::sync::Mutex::new(vec![]));
+        let client = {
+            let reported = reported.clone();
+            SmmoClient::builder("key".into())
+                .base_url(server.url())
+                .on_decode_warnings(move |_, warnings| {
+                    reported.lock().unwrap().extend(warnings.iter().cloned())
+                })
+                .build()
+                .unwrap()
+        };
+
+        let item = client.get_item_by_id(1).await.unwrap();
+        assert_eq!(item.stat1, Some(ItemStat::Unknown("luck".into())));
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].path, "$.stat1");
+    }
+
+    #[tokio::test]
+    async fn test_strict_decode_fails() {
+        let server = TestServer::start(vec![MockResponse::json(
+            &ITEM.replace(r#""stat1": "str""#, r#""stat1": "luck""#),
+        )])
+        .await;
+        let client = SmmoClient::builder("key".into())
+            .base_url(server.url())
+            .decode_mode(DecodeMode::Strict)
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            client.get_item_by_id(1).await,
+            Err(Error::JsonDecode { .. })
+        ));
+    }
+}
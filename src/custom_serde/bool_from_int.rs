@@ -1,6 +1,6 @@
 use serde::{
     de::{self, Unexpected},
-    Deserialize, Deserializer,
+    Deserialize, Deserializer, Serializer,
 };
 
 pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
@@ -17,6 +17,10 @@ where
     }
 }
 
+pub fn serialize<S: Serializer>(value: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u8(u8::from(*value))
+}
+
 #[cfg(test)]
 mod test_bool_from_int_deserializing {
     use serde::Deserialize;
@@ -1,6 +1,6 @@
 use serde::{
     de::{self, Unexpected},
-    Deserialize, Deserializer,
+    Deserialize, Deserializer, Serializer,
 };
 
 pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
@@ -18,6 +18,10 @@ where
     }
 }
 
+pub fn serialize<S: Serializer>(value: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(if *value { "1" } else { "0" })
+}
+
 #[cfg(test)]
 mod test_bool_from_int_str_deserializing {
     use serde::Deserialize;
@@ -1,13 +1,5 @@
-use chrono::{DateTime, TimeZone, Utc};
-use serde::{Deserialize, Deserializer};
-
-// pub fn serialize_option_datefmt<S: Serializer>(
-//     time: &Option<DateTime<Utc>>,
-//     serializer: S,
-// ) -> Result<S::Ok, S::Error> {
-//     time.map(|dt| dt.to_rfc3339().serialize(serializer))
-//         .or("".serialize(serializer))
-// }
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serializer};
 
 const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
@@ -16,7 +8,8 @@ where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
-    Utc.datetime_from_str(&s, FORMAT)
+    NaiveDateTime::parse_from_str(&s, FORMAT)
+        .map(|dt| Utc.from_utc_datetime(&dt))
         .map_err(serde::de::Error::custom)
 }
 
@@ -32,3 +25,14 @@ where
     let v = Option::deserialize(deserializer)?;
     Ok(v.map(|Wrapper(a)| a))
 }
+
+/// The inverse of [`deserialize_option_datefmt`]: the api's format, or null.
+pub fn serialize_option_datefmt<S: Serializer>(
+    time: &Option<DateTime<Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match time {
+        Some(time) => serializer.collect_str(&time.format(FORMAT)),
+        None => serializer.serialize_none(),
+    }
+}
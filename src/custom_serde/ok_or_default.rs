@@ -1,13 +1,24 @@
-use serde::{Deserialize, Deserializer};
+use serde::{de::Error, Deserialize, Deserializer};
 use serde_json::Value;
 
+use crate::decode::{self, DecodeWarningKind};
+
 pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
 where
     T: Deserialize<'de> + Default,
     D: Deserializer<'de>,
 {
     let v: Value = Deserialize::deserialize(deserializer)?;
-    Ok(T::deserialize(v).unwrap_or_default())
+    match T::deserialize(v) {
+        Ok(t) => Ok(t),
+        Err(why) => {
+            decode::report(DecodeWarningKind::Coerced {
+                reason: why.to_string(),
+            })
+            .map_err(D::Error::custom)?;
+            Ok(T::default())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,598 @@
+//! Decoding api responses, and reporting where they don't match the models.
+//!
+//! The api changes without notice: fields are added, removed, or start holding values the models
+//! don't expect. In [`DecodeMode::Lenient`] (the default) such responses are decoded as well as
+//! they can be, and every difference is reported as a [`DecodeWarning`]; in
+//! [`DecodeMode::Strict`] the first difference fails the decode.
+
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+    slice, vec,
+};
+
+use serde::{
+    de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor},
+    forward_to_deserialize_any, Deserializer,
+};
+use serde_json::Value;
+
+use crate::models::{item::ItemStat, SmmoModel};
+
+/// How to handle responses that don't exactly match the models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodeMode {
+    /// Fail on anything unexpected: unknown or missing fields, values that had to be replaced
+    /// with a default, enum values the crate doesn't know about, and stats without a modifier.
+    Strict,
+    /// Decode what can be decoded, and report everything unexpected as [`DecodeWarnings`].
+    #[default]
+    Lenient,
+}
+
+/// Something unexpected in a response, and where in the json it was.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeWarning {
+    /// Where the value is in the response, e.g. `$.weapon.stat1modifier` or `$[2].name`.
+    pub path: String,
+    pub kind: DecodeWarningKind,
+}
+
+impl Display for DecodeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeWarningKind {
+    /// The value could not be decoded, so the field was given its default value instead.
+    Coerced { reason: String },
+    /// A field the model doesn't have. It was ignored.
+    UnknownField,
+    /// A field the model has, but the response didn't. It was given its default value.
+    MissingField,
+    /// A value of an enum that the crate doesn't know about. It was kept in the `Unknown` variant
+    /// of the enum.
+    UnknownVariant {
+        type_name: &'static str,
+        value: String,
+    },
+    /// A stat without a (valid) modifier, so its bonus is unknown. It is left out of the
+    /// model's [`stats`](crate::models::stats::StatSlots::stats).
+    MissingModifier { stat: ItemStat },
+}
+
+impl Display for DecodeWarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeWarningKind::Coerced { reason } => write!(f, "could not be decoded ({})", reason),
+            DecodeWarningKind::UnknownField => f.write_str("unknown field"),
+            DecodeWarningKind::MissingField => f.write_str("missing field"),
+            DecodeWarningKind::UnknownVariant { type_name, value } => {
+                write!(f, "unknown {} {:?}", type_name, value)
+            }
+            DecodeWarningKind::MissingModifier { stat } => {
+                write!(f, "stat {} has no modifier", stat)
+            }
+        }
+    }
+}
+
+/// Everything unexpected found while decoding a response, in the order it was found.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DecodeWarnings(Vec<DecodeWarning>);
+
+impl DecodeWarnings {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, DecodeWarning> {
+        self.0.iter()
+    }
+}
+
+impl IntoIterator for DecodeWarnings {
+    type Item = DecodeWarning;
+    type IntoIter = vec::IntoIter<DecodeWarning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a DecodeWarnings {
+    type Item = &'a DecodeWarning;
+    type IntoIter = slice::Iter<'a, DecodeWarning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// One warning per line.
+impl Display for DecodeWarnings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let warnings = self.iter().map(ToString::to_string).collect::<Vec<_>>();
+        f.write_str(&warnings.join("\n"))
+    }
+}
+
+/// Decodes `json` as a `T`, as the client does with responses.
+///
+/// Unknown and missing fields are found by comparing `json` with what the decoded model
+/// serializes back to, so they are reported after everything else, and fields of
+/// `#[serde(flatten)]`ed structs (such as the player in
+/// [`SelfPlayer`](crate::models::smmo_player::SelfPlayer)) are checked like any other.
+pub fn decode<T: SmmoModel>(
+    json: &str,
+    mode: DecodeMode,
+) -> Result<(T, DecodeWarnings), serde_json::Error> {
+    decode_value(serde_json::from_str(json)?, mode)
+}
+
+/// Like [`decode`], for json that has already been parsed.
+pub fn decode_value<T: SmmoModel>(
+    value: Value,
+    mode: DecodeMode,
+) -> Result<(T, DecodeWarnings), serde_json::Error> {
+    let outer = CONTEXT.with(|context| context.replace(Some(Context::new(mode))));
+    let result = T::deserialize(Tracked(value.clone())).and_then(|decoded| {
+        let serialized = serde_json::to_value(&decoded)?;
+        check_fields(&value, &serialized, T::OPTIONAL_FIELDS).map_err(de::Error::custom)?;
+        Ok(decoded)
+    });
+    let context = CONTEXT
+        .with(|context| context.replace(outer))
+        .expect("context is set for the duration of the decode");
+    result.map(|decoded| (decoded, DecodeWarnings(context.warnings)))
+}
+
+/// Reports something unexpected at the current path. In strict mode this returns the message of
+/// the error to fail with.
+///
+/// Outside of [`decode`] (e.g. plain `serde_json::from_str`) nothing is reported and this always
+/// succeeds, so models decode leniently and silently as they always have.
+pub(crate) fn report(kind: DecodeWarningKind) -> Result<(), String> {
+    CONTEXT.with(|context| match &mut *context.borrow_mut() {
+        Some(context) => {
+            let warning = DecodeWarning {
+                path: context.path(),
+                kind,
+            };
+            match context.mode {
+                DecodeMode::Strict => Err(warning.to_string()),
+                DecodeMode::Lenient => {
+                    context.warnings.push(warning);
+                    Ok(())
+                }
+            }
+        }
+        None => Ok(()),
+    })
+}
+
+/// Like [`report`], for a field of the object at the current path.
+pub(crate) fn report_field(field: &str, kind: DecodeWarningKind) -> Result<(), String> {
+    at(PathSegment::Key(field.to_string()), || report(kind))
+}
+
+thread_local! {
+    /// The decode in progress on this thread. Decoding never awaits, so a thread local is enough to
+    /// let `deserialize_with` functions and manual `Deserialize` impls report to it.
+    static CONTEXT: RefCell<Option<Context>> = const { RefCell::new(None) };
+}
+
+struct Context {
+    mode: DecodeMode,
+    path: Vec<PathSegment>,
+    warnings: Vec<DecodeWarning>,
+}
+
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl Context {
+    fn new(mode: DecodeMode) -> Self {
+        Self {
+            mode,
+            path: vec![],
+            warnings: vec![],
+        }
+    }
+
+    fn path(&self) -> String {
+        let mut path = "$".to_string();
+        for segment in &self.path {
+            match segment {
+                PathSegment::Key(key) => {
+                    path.push('.');
+                    path.push_str(key);
+                }
+                PathSegment::Index(index) => path.push_str(&format!("[{}]", index)),
+            }
+        }
+        path
+    }
+}
+
+/// Runs `f` with `segment` appended to the current path.
+fn at<T>(segment: PathSegment, f: impl FnOnce() -> T) -> T {
+    CONTEXT.with(|context| {
+        if let Some(context) = &mut *context.borrow_mut() {
+            context.path.push(segment);
+        }
+    });
+    let result = f();
+    CONTEXT.with(|context| {
+        if let Some(context) = &mut *context.borrow_mut() {
+            context.path.pop();
+        }
+    });
+    result
+}
+
+/// Deserializes a json value, keeping track of the current path.
+struct Tracked(Value);
+
+impl<'de> Deserializer<'de> for Tracked {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Array(values) => visitor.visit_seq(TrackedSeq {
+                values: values.into_iter().enumerate(),
+            }),
+            Value::Object(map) => visitor.visit_map(TrackedMap {
+                entries: map.into_iter(),
+                value: None,
+            }),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(Tracked(value)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Reports the fields that are only in `json` as unknown, and those that are only in
+/// `serialized` (the decoded model, serialized back to the api's format) as missing, unless they
+/// are in `optional`. Serde's own list of a struct's fields would miss those of
+/// `#[serde(flatten)]`ed structs.
+fn check_fields(json: &Value, serialized: &Value, optional: &[&str]) -> Result<(), String> {
+    match (json, serialized) {
+        (Value::Object(json), Value::Object(serialized)) => {
+            for key in json.keys() {
+                if !serialized.contains_key(key) {
+                    report_field(key, DecodeWarningKind::UnknownField)?;
+                }
+            }
+            for (key, serialized) in serialized {
+                match json.get(key) {
+                    Some(json) => at(PathSegment::Key(key.clone()), || {
+                        check_fields(json, serialized, &[])
+                    })?,
+                    None if optional.contains(&key.as_str()) => {}
+                    None => report_field(key, DecodeWarningKind::MissingField)?,
+                }
+            }
+        }
+        (Value::Array(json), Value::Array(serialized)) => {
+            for (index, (json, serialized)) in json.iter().zip(serialized).enumerate() {
+                at(PathSegment::Index(index), || {
+                    check_fields(json, serialized, &[])
+                })?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+struct TrackedSeq {
+    values: std::iter::Enumerate<vec::IntoIter<Value>>,
+}
+
+impl<'de> SeqAccess<'de> for TrackedSeq {
+    type Error = serde_json::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        match self.values.next() {
+            Some((index, value)) => at(PathSegment::Index(index), || {
+                seed.deserialize(Tracked(value))
+            })
+            .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+struct TrackedMap {
+    entries: serde_json::map::IntoIter,
+    /// The key and value of the entry whose key was just deserialized.
+    value: Option<(String, Value)>,
+}
+
+impl<'de> MapAccess<'de> for TrackedMap {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                let deserialized = seed.deserialize(
+                    IntoDeserializer::<serde_json::Error>::into_deserializer(key.as_str()),
+                )?;
+                self.value = Some((key, value));
+                Ok(Some(deserialized))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (key, value) = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value is missing"))?;
+        at(PathSegment::Key(key), || seed.deserialize(Tracked(value)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+#[cfg(test)]
+mod test_decode {
+    use super::*;
+    use crate::models::{
+        item::{Item, ItemStat, ItemType},
+        pet::PlayerPets,
+        player_equipment::PlayerEquipment,
+        smmo_player::SelfPlayer,
+    };
+    use crate::test_fixtures::{ITEM, PLAYER_EQUIPMENT, SELF_PLAYER};
+
+    fn self_player_without(fields: &[&str]) -> Value {
+        let mut json = serde_json::from_str::<Value>(SELF_PLAYER).unwrap();
+        for field in fields {
+            json.as_object_mut().unwrap().remove(*field);
+        }
+        json
+    }
+
+    /// Sorted by path, as unknown fields are found after the other fields of their object with
+    /// the `extra-fields` feature.
+    fn sorted(warnings: &DecodeWarnings) -> Vec<&DecodeWarning> {
+        let mut warnings = warnings.iter().collect::<Vec<_>>();
+        warnings.sort_by(|a, b| a.path.cmp(&b.path));
+        warnings
+    }
+
+    fn paths(warnings: &DecodeWarnings) -> Vec<&str> {
+        sorted(warnings)
+            .into_iter()
+            .map(|warning| warning.path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_no_warnings() {
+        let (_, warnings) = decode::<Item>(ITEM, DecodeMode::Strict).unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_lenient() {
+        let json = ITEM
+            .replace(r#""stat1modifier": 1"#, r#""stat1modifier": "lots""#)
+            .replace(r#""type": "Weapon""#, r#""type": "Lootbox""#)
+            .replace(r#""locked": 0"#, r#""locked": 0, "glows": true"#);
+
+        let (item, warnings) = decode::<Item>(&json, DecodeMode::Lenient).unwrap();
+        assert_eq!(item.stat1modifier, None);
+        assert_eq!(item.item_type, ItemType::Unknown("Lootbox".into()));
+        assert_eq!(
+            sorted(&warnings)
+                .into_iter()
+                .map(|w| &w.kind)
+                .collect::<Vec<_>>(),
+            vec![
+                &DecodeWarningKind::UnknownField,
+                &DecodeWarningKind::Coerced {
+                    reason: r#"invalid type: string "lots", expected u32"#.into()
+                },
+                &DecodeWarningKind::MissingModifier {
+                    stat: ItemStat::Str
+                },
+                &DecodeWarningKind::UnknownVariant {
+                    type_name: "ItemType",
+                    value: "Lootbox".into()
+                },
+            ]
+        );
+        assert_eq!(
+            paths(&warnings),
+            vec!["$.glows", "$.stat1modifier", "$.stat1modifier", "$.type"]
+        );
+    }
+
+    #[test]
+    fn test_strict() {
+        let json = ITEM.replace(r#""stat1": "str""#, r#""stat1": 5"#);
+        let why = decode::<Item>(&json, DecodeMode::Strict).unwrap_err();
+        assert!(why.to_string().starts_with("$.stat1: could not be decoded"));
+
+        let json = ITEM.replace(r#""locked": 0"#, r#""locked": 0, "glows": true"#);
+        let why = decode::<Item>(&json, DecodeMode::Strict).unwrap_err();
+        assert_eq!(why.to_string(), "$.glows: unknown field");
+    }
+
+    #[test]
+    fn test_flattened_fields_are_checked() {
+        let mut json = self_player_without(&["guild"]);
+        json["pronouns"] = "they/them".into();
+
+        let why = decode_value::<SelfPlayer>(json.clone(), DecodeMode::Strict).unwrap_err();
+        assert_eq!(why.to_string(), "$.pronouns: unknown field");
+
+        let (player, warnings) = decode_value::<SelfPlayer>(json, DecodeMode::Lenient).unwrap();
+        assert_eq!(player.guild, None);
+        assert_eq!(
+            sorted(&warnings)
+                .into_iter()
+                .map(|w| (w.path.as_str(), &w.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("$.guild", &DecodeWarningKind::MissingField),
+                ("$.pronouns", &DecodeWarningKind::UnknownField),
+            ]
+        );
+    }
+
+    /// With the `extra-fields` feature every model is flattened, not just [`SelfPlayer`].
+    #[cfg(feature = "extra-fields")]
+    #[test]
+    fn test_extra_fields_models_are_checked() {
+        use crate::models::smmo_player::SmmoPlayer;
+
+        let mut json = self_player_without(&[
+            "guild",
+            "energy",
+            "max_energy",
+            "quest_points",
+            "max_quest_points",
+            "bank",
+            "diamonds",
+        ]);
+        json["pronouns"] = "they/them".into();
+
+        let why = decode_value::<SmmoPlayer>(json.clone(), DecodeMode::Strict).unwrap_err();
+        assert_eq!(why.to_string(), "$.pronouns: unknown field");
+
+        let (player, warnings) = decode_value::<SmmoPlayer>(json, DecodeMode::Lenient).unwrap();
+        assert_eq!(
+            player.extra.get("pronouns"),
+            Some(&Value::from("they/them"))
+        );
+        assert_eq!(paths(&warnings), vec!["$.guild", "$.pronouns"]);
+
+        let json = self_player_without(&["guild"]);
+        let why = decode_value::<SelfPlayer>(json, DecodeMode::Strict).unwrap_err();
+        assert_eq!(why.to_string(), "$.guild: missing field");
+    }
+
+    #[test]
+    fn test_defaulted_fields_may_be_missing() {
+        let (equipment, warnings) =
+            decode::<PlayerEquipment>(r#"{ "weapon": null }"#, DecodeMode::Strict).unwrap();
+        assert_eq!(equipment.items().count(), 0);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_nested_paths() {
+        let json = r#"[
+            { "item_id": 1, "name": "Cat", "level": 1, "stat1": "str", "stat1modifier": 1,
+              "stat2": null, "stat2modifier": null, "stat3": null, "stat3modifier": null },
+            { "item_id": 2, "name": "Dog", "level": 1, "stat1": "luck", "stat1modifier": 1,
+              "stat2": null, "stat2modifier": null, "stat3": null, "stat3modifier": null,
+              "mood": "happy" }
+        ]"#;
+
+        let (pets, warnings) = decode::<PlayerPets>(json, DecodeMode::Lenient).unwrap();
+        assert_eq!(pets.0[1].stat1, Some(ItemStat::Unknown("luck".into())));
+        assert_eq!(paths(&warnings), vec!["$[1].mood", "$[1].stat1"]);
+        let mut lines = warnings
+            .to_string()
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        lines.sort();
+        assert_eq!(
+            lines,
+            vec![
+                "$[1].mood: unknown field",
+                "$[1].stat1: unknown ItemStat \"luck\""
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stats_without_modifier() {
+        let equipment =
+            PLAYER_EQUIPMENT.replace(r#""stat2modifier": 100"#, r#""stat2modifier": null"#);
+        let (equipment, warnings) =
+            decode::<PlayerEquipment>(&equipment, DecodeMode::Lenient).unwrap();
+        assert_eq!(equipment.pet.unwrap().stat2modifier, None);
+        assert_eq!(
+            warnings.to_string(),
+            "$.pet.stat2modifier: stat Hp has no modifier"
+        );
+
+        let pets = r#"[
+            { "item_id": 1, "name": "Cat", "level": 1, "stat1": "str", "stat1modifier": 1,
+              "stat2": null, "stat2modifier": null, "stat3": "def", "stat3modifier": null }
+        ]"#;
+        let (_, warnings) = decode::<PlayerPets>(pets, DecodeMode::Lenient).unwrap();
+        assert_eq!(paths(&warnings), vec!["$[0].stat3modifier"]);
+
+        let item = ITEM.replace(r#""stat1modifier": 1"#, r#""stat1modifier": null"#);
+        let why = decode::<Item>(&item, DecodeMode::Strict).unwrap_err();
+        assert!(why
+            .to_string()
+            .contains("$.stat1modifier: stat Str has no modifier"));
+    }
+
+    #[test]
+    fn test_plain_serde_is_unaffected() {
+        let json = ITEM.replace(r#""stat1": "str""#, r#""stat1": 5"#);
+        assert_eq!(serde_json::from_str::<Item>(&json).unwrap().stat1, None);
+    }
+}
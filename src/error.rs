@@ -0,0 +1,202 @@
+use std::fmt::{self, Display};
+
+use reqwest::{header::HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+
+/// An error from any of the [`SmmoClient`](crate::client::SmmoClient) endpoints.
+///
+/// Every variant records the name of the model that was being fetched
+/// ([`SmmoModel::TYPE_NAME`](crate::models::SmmoModel::TYPE_NAME)), so errors from different
+/// endpoints can be mixed freely with `?`.
+#[derive(Debug)]
+pub enum Error {
+    /// The api responded with an error instead of the expected model.
+    Api {
+        error: ApiErrorType,
+        expected: &'static str,
+        /// The status of the response.
+        status: StatusCode,
+        /// The headers of the response.
+        headers: Box<HeaderMap>,
+    },
+    /// The request could not be sent, or its response could not be read.
+    Http {
+        source: reqwest::Error,
+        expected: &'static str,
+    },
+    /// Unable to deserialize the api response; most likely means that the response structure changed.
+    JsonDecode {
+        source: serde_json::Error,
+        expected: &'static str,
+        /// The url the response came from.
+        url: String,
+        /// The status of the response.
+        status: StatusCode,
+        /// The headers of the response.
+        headers: Box<HeaderMap>,
+        /// The body of the response, exactly as it was received.
+        body: Vec<u8>,
+    },
+}
+
+impl Error {
+    /// The name of the model that was being fetched.
+    pub fn expected(&self) -> &'static str {
+        match self {
+            Error::Api { expected, .. }
+            | Error::Http { expected, .. }
+            | Error::JsonDecode { expected, .. } => expected,
+        }
+    }
+
+    /// The status of the response, unless no response was received.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Api { status, .. } | Error::JsonDecode { status, .. } => Some(*status),
+            Error::Http { source, .. } => source.status(),
+        }
+    }
+
+    /// The headers of the response, unless no response was received.
+    pub fn headers(&self) -> Option<&HeaderMap> {
+        match self {
+            Error::Api { headers, .. } | Error::JsonDecode { headers, .. } => Some(headers),
+            Error::Http { .. } => None,
+        }
+    }
+
+    /// The error returned by the api, if this is an [`Error::Api`].
+    pub fn api_error(&self) -> Option<&ApiErrorType> {
+        match self {
+            Error::Api { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Api {
+                error, expected, ..
+            } => {
+                write!(
+                    f,
+                    "the smmo api returned an error fetching {}: {}",
+                    expected, error
+                )
+            }
+            Error::Http { expected, .. } => {
+                write!(f, "unable to fetch {} from the smmo api", expected)
+            }
+            Error::JsonDecode { expected, url, .. } => write!(
+                f,
+                "unable to decode the response from `{}` as {}",
+                url, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Api { error, .. } => Some(error),
+            Error::Http { source, .. } => Some(source),
+            Error::JsonDecode { source, .. } => Some(source),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ApiErrorType {
+    /// Error from the api; means the item was not found.
+    /// TODO: Include the invalid item id in the variant.
+    #[serde(alias = "item not found")]
+    ItemNotFound,
+    /// Error from the api; means the api_key is not valid.
+    #[serde(alias = "unauthenticated")]
+    Unauthenticated,
+}
+
+impl Display for ApiErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ApiErrorType::ItemNotFound => "item not found",
+            ApiErrorType::Unauthenticated => "unauthenticated, check the api key",
+        })
+    }
+}
+
+impl std::error::Error for ApiErrorType {}
+
+/// The body of an error response from the api.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApiErrorResponse {
+    pub error: ApiErrorType,
+}
+
+#[cfg(test)]
+mod test_error {
+    use std::error::Error as _;
+
+    use super::*;
+
+    #[test]
+    fn test_api_error_display() {
+        let error = Error::Api {
+            error: ApiErrorType::ItemNotFound,
+            expected: "Item",
+            status: StatusCode::OK,
+            headers: Box::default(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "the smmo api returned an error fetching Item: item not found"
+        );
+        assert_eq!(error.status(), Some(StatusCode::OK));
+        assert_eq!(error.source().unwrap().to_string(), "item not found");
+    }
+
+    #[test]
+    fn test_json_decode_error_display() {
+        let error = Error::JsonDecode {
+            source: serde_json::from_str::<u32>("nope").unwrap_err(),
+            expected: "Orphanage",
+            url: "https://api.simple-mmo.com/v1/orphanage".into(),
+            status: StatusCode::BAD_GATEWAY,
+            headers: Box::default(),
+            body: b"nope".to_vec(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "unable to decode the response from `https://api.simple-mmo.com/v1/orphanage` as Orphanage"
+        );
+        assert_eq!(error.expected(), "Orphanage");
+        assert!(error.source().unwrap().is::<serde_json::Error>());
+    }
+
+    #[test]
+    fn test_question_mark_across_endpoints() {
+        fn item() -> Result<(), Error> {
+            Err(Error::Api {
+                error: ApiErrorType::ItemNotFound,
+                expected: "Item",
+                status: StatusCode::OK,
+                headers: Box::default(),
+            })
+        }
+
+        fn orphanage_then_item() -> Result<(), Error> {
+            let orphanage: Result<(), Error> = Ok(());
+            orphanage?;
+            item()?;
+            Ok(())
+        }
+
+        assert_eq!(
+            orphanage_then_item().unwrap_err().api_error(),
+            Some(&ApiErrorType::ItemNotFound)
+        );
+    }
+}
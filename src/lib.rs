@@ -1,3 +1,17 @@
+pub mod catalog;
 pub mod client;
 mod custom_serde;
+pub mod decode;
+pub mod error;
 pub mod models;
+pub mod rate_limit;
+mod redact;
+pub mod retry;
+pub mod search;
+
+pub use error::Error;
+
+#[cfg(test)]
+mod test_fixtures;
+#[cfg(test)]
+mod test_server;
@@ -0,0 +1,103 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "extra-fields")]
+use crate::models::extra::{ExtraFields, UnmappedFields};
+use crate::models::{smmo_player::UserId, SmmoModel};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DiamondMarket(pub Vec<DiamondListing>);
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for DiamondMarket {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.0.collect_unmapped_fields(path, fields);
+    }
+}
+
+impl DiamondMarket {
+    /// The lowest price per diamond of any listing with diamonds left.
+    pub fn cheapest_price(&self) -> Option<u64> {
+        self.0
+            .iter()
+            .filter(|listing| listing.diamonds_remaining > 0)
+            .map(|listing| listing.price_per_diamond)
+            .min()
+    }
+
+    /// The average price per diamond, weighted by the diamonds remaining in each listing.
+    /// `None` if there are no diamonds for sale.
+    pub fn volume_weighted_average_price(&self) -> Option<f64> {
+        let (total_price, total_diamonds) =
+            self.0
+                .iter()
+                .fold((0u128, 0u128), |(price, diamonds), listing| {
+                    let remaining = u128::from(listing.diamonds_remaining);
+                    (
+                        price + u128::from(listing.price_per_diamond) * remaining,
+                        diamonds + remaining,
+                    )
+                });
+
+        if total_diamonds == 0 {
+            None
+        } else {
+            Some(total_price as f64 / total_diamonds as f64)
+        }
+    }
+}
+
+impl SmmoModel for DiamondMarket {
+    const TYPE_NAME: &'static str = "Vec<DiamondListing>";
+}
+
+impl Display for DiamondMarket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string_pretty(&self).map_err(|_| fmt::Error)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DiamondListing {
+    pub seller: UserId,
+    /// In gold.
+    pub price_per_diamond: u64,
+    pub diamonds_remaining: u32,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for DiamondListing {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
+}
+
+#[cfg(test)]
+mod test_diamond_market_deserialize {
+    use super::*;
+
+    #[test]
+    fn test_diamond_market() {
+        let json = r#"[
+            { "seller": 1, "price_per_diamond": 30000, "diamonds_remaining": 10 },
+            { "seller": 2, "price_per_diamond": 25000, "diamonds_remaining": 0 },
+            { "seller": 3, "price_per_diamond": 28000, "diamonds_remaining": 30 }
+        ]"#;
+
+        let market = serde_json::from_str::<DiamondMarket>(json).unwrap();
+        assert_eq!(market.0[0].seller, UserId::new(1));
+        assert_eq!(market.cheapest_price(), Some(28000));
+        assert_eq!(market.volume_weighted_average_price(), Some(28500.0));
+    }
+
+    #[test]
+    fn test_empty_diamond_market() {
+        let market = DiamondMarket(vec![]);
+        assert_eq!(market.cheapest_price(), None);
+        assert_eq!(market.volume_weighted_average_price(), None);
+    }
+}
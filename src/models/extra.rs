@@ -0,0 +1,151 @@
+//! Fields the api sends that the models don't have (yet).
+//!
+//! With the `extra-fields` feature, every model struct has an `extra` field that collects the
+//! fields of its json object that no other field of the struct took, so new data can be read
+//! before the crate supports it. They are serialized back out alongside the other fields.
+//!
+//! Collected fields are still reported as unknown fields in the
+//! [`DecodeWarnings`](crate::decode::DecodeWarnings) (and rejected in strict mode).
+
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::decode::{self, DecodeWarningKind};
+
+/// The fields of a json object that the model it was decoded into has no field for, by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct ExtraFields(pub BTreeMap<String, Value>);
+
+impl Deref for ExtraFields {
+    type Target = BTreeMap<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for ExtraFields {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for ExtraFields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = BTreeMap::<String, Value>::deserialize(deserializer)?;
+        for name in fields.keys() {
+            decode::report_field(name, DecodeWarningKind::UnknownField)
+                .map_err(D::Error::custom)?;
+        }
+        Ok(Self(fields))
+    }
+}
+
+/// Compares the fields as json text, since json values have no order of their own. Only here so
+/// that models ordered by all of their fields (such as [`Item`](crate::models::item::Item)) can
+/// stay that way.
+impl PartialOrd for ExtraFields {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ExtraFields {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let as_text = |fields: &Self| {
+            fields
+                .iter()
+                .map(|(name, value)| (name.clone(), value.to_string()))
+                .collect::<Vec<_>>()
+        };
+        as_text(self).cmp(&as_text(other))
+    }
+}
+
+/// Lists the fields of a model, and of the models inside it, that were collected into `extra`
+/// fields.
+pub trait UnmappedFields {
+    /// The paths of the unmapped fields, in the same form as
+    /// [`DecodeWarning::path`](crate::decode::DecodeWarning::path), e.g. `$.weapon.glow`.
+    fn unmapped_fields(&self) -> Vec<String> {
+        let mut fields = vec![];
+        self.collect_unmapped_fields("$", &mut fields);
+        fields
+    }
+
+    /// Appends the paths of the unmapped fields to `fields`, with `path` being the path of this
+    /// model.
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>);
+}
+
+impl UnmappedFields for ExtraFields {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        fields.extend(self.keys().map(|name| format!("{}.{}", path, name)));
+    }
+}
+
+impl<T: UnmappedFields> UnmappedFields for Option<T> {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        if let Some(model) = self {
+            model.collect_unmapped_fields(path, fields);
+        }
+    }
+}
+
+impl<T: UnmappedFields> UnmappedFields for Vec<T> {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        for (index, model) in self.iter().enumerate() {
+            model.collect_unmapped_fields(&format!("{}[{}]", path, index), fields);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_extra_fields {
+    use super::*;
+    use crate::{
+        decode::DecodeMode,
+        models::{player_equipment::PlayerEquipment, smmo_player::SelfPlayer},
+        test_fixtures::{PLAYER_EQUIPMENT, SELF_PLAYER},
+    };
+
+    #[test]
+    fn test_captures_and_lists_unmapped_fields() {
+        let mut json = serde_json::from_str::<Value>(PLAYER_EQUIPMENT).unwrap();
+        json["weapon"]["glow"] = "blue".into();
+        json["ring"] = Value::Null;
+
+        let (equipment, warnings) =
+            decode::decode_value::<PlayerEquipment>(json.clone(), DecodeMode::Lenient).unwrap();
+        assert_eq!(
+            equipment.weapon.as_ref().unwrap().extra.get("glow"),
+            Some(&Value::from("blue"))
+        );
+        assert_eq!(
+            equipment.unmapped_fields(),
+            vec!["$.ring".to_string(), "$.weapon.glow".to_string()]
+        );
+        assert_eq!(warnings.len(), 2);
+
+        let round_trip = serde_json::to_value(&equipment).unwrap();
+        assert_eq!(round_trip["weapon"]["glow"], "blue");
+
+        assert!(decode::decode_value::<PlayerEquipment>(json, DecodeMode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_flattened_player() {
+        let mut json = serde_json::from_str::<Value>(SELF_PLAYER).unwrap();
+        json["pronouns"] = "they/them".into();
+
+        let player = serde_json::from_value::<SelfPlayer>(json).unwrap();
+        assert_eq!(player.unmapped_fields(), vec!["$.pronouns".to_string()]);
+    }
+}
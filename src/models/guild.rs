@@ -0,0 +1,329 @@
+use std::fmt::{self, Display};
+
+use serde::{
+    de::{self, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+#[cfg(feature = "extra-fields")]
+use crate::models::extra::{ExtraFields, UnmappedFields};
+use crate::{
+    custom_serde::bool_from_int,
+    models::{smmo_player::UserId, SmmoModel},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Guild {
+    pub id: GuildId,
+    pub name: String,
+    pub tag: String,
+    pub leader: UserId,
+    pub exp: u64,
+    pub icon: String,
+    /// Passive guilds can't take part in guild wars.
+    #[serde(with = "bool_from_int")]
+    pub passive: bool,
+    pub member_count: u32,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for Guild {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "sql", derive(sqlx::Type))]
+#[cfg_attr(feature = "sql", sqlx(transparent))]
+pub struct GuildId(u32);
+
+impl GuildId {
+    pub fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    pub fn inner(self) -> u32 {
+        self.0
+    }
+}
+
+impl Display for GuildId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl SmmoModel for Guild {
+    const TYPE_NAME: &'static str = "Guild";
+}
+
+impl Display for Guild {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string_pretty(&self).map_err(|_| fmt::Error)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GuildMember {
+    pub user_id: UserId,
+    pub name: String,
+    pub level: u32,
+    /// The member's rank within the guild, e.g. `Leader` or `Member`.
+    pub position: String,
+    #[serde(with = "bool_from_int")]
+    pub safe_mode: bool,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for GuildMember {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GuildMembers(pub Vec<GuildMember>);
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for GuildMembers {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.0.collect_unmapped_fields(path, fields);
+    }
+}
+
+impl SmmoModel for GuildMembers {
+    const TYPE_NAME: &'static str = "Vec<GuildMember>";
+}
+
+impl Display for GuildMembers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string_pretty(&self).map_err(|_| fmt::Error)?)
+    }
+}
+
+/// A war between two guilds. Either guild may be the one that was asked about.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GuildWar {
+    pub guild_1: GuildWarSide,
+    pub guild_2: GuildWarSide,
+    pub status: WarStatus,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for GuildWar {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+        self.guild_1
+            .collect_unmapped_fields(&format!("{}.guild_1", path), fields);
+        self.guild_2
+            .collect_unmapped_fields(&format!("{}.guild_2", path), fields);
+    }
+}
+
+impl GuildWar {
+    /// The side of the war the given guild is on, if it is taking part.
+    pub fn side(&self, guild_id: GuildId) -> Option<&GuildWarSide> {
+        if self.guild_1.id == guild_id {
+            Some(&self.guild_1)
+        } else if self.guild_2.id == guild_id {
+            Some(&self.guild_2)
+        } else {
+            None
+        }
+    }
+
+    /// The side of the war opposing the given guild, if it is taking part.
+    pub fn opponent(&self, guild_id: GuildId) -> Option<&GuildWarSide> {
+        if self.guild_1.id == guild_id {
+            Some(&self.guild_2)
+        } else if self.guild_2.id == guild_id {
+            Some(&self.guild_1)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GuildWarSide {
+    /// Pass to [`SmmoClient::get_guild`](crate::client::SmmoClient::get_guild) for the rest of
+    /// the guild's information.
+    pub id: GuildId,
+    pub name: String,
+    pub kills: u32,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for GuildWarSide {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GuildWars(pub Vec<GuildWar>);
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for GuildWars {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.0.collect_unmapped_fields(path, fields);
+    }
+}
+
+impl SmmoModel for GuildWars {
+    const TYPE_NAME: &'static str = "Vec<GuildWar>";
+}
+
+impl Display for GuildWars {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string_pretty(&self).map_err(|_| fmt::Error)?)
+    }
+}
+
+/// The status of a guild war. Sent to and received from the api as a number.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum WarStatus {
+    Ongoing,
+    /// Both guilds have agreed to pause the war.
+    Hold,
+    Ended,
+}
+
+impl WarStatus {
+    pub fn as_u8(self) -> u8 {
+        match self {
+            WarStatus::Ongoing => 1,
+            WarStatus::Hold => 2,
+            WarStatus::Ended => 3,
+        }
+    }
+}
+
+impl Display for WarStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WarStatus::Ongoing => "Ongoing",
+            WarStatus::Hold => "Hold",
+            WarStatus::Ended => "Ended",
+        })
+    }
+}
+
+impl Serialize for WarStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.as_u8())
+    }
+}
+
+impl<'de> Deserialize<'de> for WarStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match u8::deserialize(deserializer)? {
+            1 => Ok(WarStatus::Ongoing),
+            2 => Ok(WarStatus::Hold),
+            3 => Ok(WarStatus::Ended),
+            other => Err(de::Error::invalid_value(
+                Unexpected::Unsigned(other as u64),
+                &"1, 2 or 3",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_guild_deserialize {
+    use super::*;
+
+    #[test]
+    fn test_guild() {
+        let json = r#"{
+            "id": 7,
+            "name": "The Lost",
+            "tag": "LOST",
+            "leader": 1234,
+            "exp": 9876543,
+            "icon": "/img/icons/guilds/7.png",
+            "passive": 0,
+            "member_count": 42
+        }"#;
+
+        assert_eq!(
+            serde_json::from_str::<Guild>(json).unwrap(),
+            Guild {
+                id: GuildId(7),
+                name: "The Lost".into(),
+                tag: "LOST".into(),
+                leader: UserId::new(1234),
+                exp: 9876543,
+                icon: "/img/icons/guilds/7.png".into(),
+                passive: false,
+                member_count: 42,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_guild_members() {
+        let json = r#"[{
+            "user_id": 1234,
+            "name": "someone",
+            "level": 150,
+            "position": "Leader",
+            "safe_mode": 1
+        }]"#;
+
+        assert_eq!(
+            serde_json::from_str::<GuildMembers>(json).unwrap(),
+            GuildMembers(vec![GuildMember {
+                user_id: UserId::new(1234),
+                name: "someone".into(),
+                level: 150,
+                position: "Leader".into(),
+                safe_mode: true,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_guild_wars() {
+        let json = r#"[{
+            "guild_1": { "id": 7, "name": "The Lost", "kills": 120 },
+            "guild_2": { "id": 9, "name": "The Found", "kills": 95 },
+            "status": 2
+        }]"#;
+
+        let wars = serde_json::from_str::<GuildWars>(json).unwrap();
+        let war = &wars.0[0];
+        assert_eq!(war.status, WarStatus::Hold);
+        assert_eq!(war.side(GuildId(7)).unwrap().kills, 120);
+        assert_eq!(war.opponent(GuildId(7)).unwrap().name, "The Found");
+        assert_eq!(war.opponent(GuildId(9)).unwrap().id, GuildId(7));
+        assert_eq!(war.opponent(GuildId(1)), None);
+    }
+
+    #[test]
+    fn test_war_status() {
+        assert_eq!(
+            serde_json::from_str::<WarStatus>("3").unwrap(),
+            WarStatus::Ended
+        );
+        assert_eq!(serde_json::to_string(&WarStatus::Ongoing).unwrap(), "1");
+        assert!(serde_json::from_str::<WarStatus>("4").is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "extra-fields")]
+use crate::models::extra::{ExtraFields, UnmappedFields};
+use crate::{
+    custom_serde::bool_from_int,
+    models::{
+        item::{Item, ItemId},
+        SmmoModel,
+    },
+};
+
+/// The items owned by the owner of the api key.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Inventory(pub Vec<InventoryEntry>);
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for Inventory {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.0.collect_unmapped_fields(path, fields);
+    }
+}
+
+impl SmmoModel for Inventory {
+    const TYPE_NAME: &'static str = "Vec<InventoryEntry>";
+}
+
+impl Display for Inventory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string_pretty(&self).map_err(|_| fmt::Error)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct InventoryEntry {
+    pub item_id: ItemId,
+    pub quantity: u32,
+    #[serde(with = "bool_from_int")]
+    pub equipped: bool,
+    #[serde(with = "bool_from_int")]
+    pub locked: bool,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for InventoryEntry {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
+}
+
+/// An [`InventoryEntry`] together with the full [`Item`] it refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InventoryItem {
+    pub entry: InventoryEntry,
+    pub item: Item,
+}
+
+impl InventoryItem {
+    /// The value of the whole stack: the item's value times the quantity owned.
+    pub fn value(&self) -> u64 {
+        u64::from(self.item.value) * u64::from(self.entry.quantity)
+    }
+}
+
+/// The value of all of the given items.
+pub fn total_value(items: &[InventoryItem]) -> u64 {
+    items.iter().map(InventoryItem::value).sum()
+}
+
+#[cfg(test)]
+mod test_inventory_deserialize {
+    use super::*;
+
+    #[test]
+    fn test_inventory() {
+        let json = r#"[
+            { "item_id": 1, "quantity": 3, "equipped": 0, "locked": 1 },
+            { "item_id": 2087, "quantity": 1, "equipped": 1, "locked": 0 }
+        ]"#;
+
+        let inventory = serde_json::from_str::<Inventory>(json).unwrap();
+        assert_eq!(inventory.0.len(), 2);
+        assert_eq!(inventory.0[0].item_id.inner(), 1);
+        assert_eq!(inventory.0[0].quantity, 3);
+        assert!(!inventory.0[0].equipped);
+        assert!(inventory.0[0].locked);
+        assert!(inventory.0[1].equipped);
+    }
+}
@@ -3,6 +3,8 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "extra-fields")]
+use crate::models::extra::{ExtraFields, UnmappedFields};
 use crate::{
     custom_serde::{bool_from_int, bool_from_int_str, empty_string_option, ok_or_default},
     models::SmmoModel,
@@ -12,6 +14,7 @@ use crate::{
 // use sqlx;
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Eq, PartialOrd, Ord)]
+#[serde(remote = "Self")]
 #[cfg_attr(feature = "sql", derive(sqlx::FromRow))]
 pub struct Item {
     pub id: ItemId,
@@ -22,10 +25,10 @@ pub struct Item {
     #[serde(rename = "type")]
     pub item_type: ItemType,
 
-    #[serde(deserialize_with = "empty_string_option::deserialize")]
+    #[serde(with = "empty_string_option")]
     pub description: Option<String>,
 
-    #[serde(deserialize_with = "bool_from_int_str::deserialize")]
+    #[serde(with = "bool_from_int_str")]
     pub equipable: bool,
 
     pub level: u32,
@@ -38,30 +41,44 @@ pub struct Item {
     pub stat1: Option<ItemStat>,
 
     #[serde(deserialize_with = "ok_or_default::deserialize")]
-    pub stat1modifier: u32,
+    pub stat1modifier: Option<u32>,
 
     #[serde(deserialize_with = "ok_or_default::deserialize")]
     pub stat2: Option<ItemStat>,
 
     #[serde(deserialize_with = "ok_or_default::deserialize")]
-    pub stat2modifier: u32,
+    pub stat2modifier: Option<u32>,
 
     #[serde(deserialize_with = "ok_or_default::deserialize")]
     pub stat3: Option<ItemStat>,
 
     #[serde(deserialize_with = "ok_or_default::deserialize")]
-    pub stat3modifier: u32,
+    pub stat3modifier: Option<u32>,
 
-    #[serde(deserialize_with = "bool_from_int::deserialize")]
+    #[serde(with = "bool_from_int")]
     pub custom_item: bool,
 
-    #[serde(deserialize_with = "bool_from_int::deserialize")]
+    #[serde(with = "bool_from_int")]
     pub tradable: bool,
 
-    #[serde(deserialize_with = "bool_from_int::deserialize")]
+    #[serde(with = "bool_from_int")]
     pub locked: bool,
+
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    #[cfg_attr(feature = "sql", sqlx(default))]
+    pub extra: ExtraFields,
 }
 
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for Item {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
+}
+
+stat_slots!(Item);
+
 // #[repr(transparent)]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize, Serialize)]
 #[cfg_attr(feature = "sql", derive(sqlx::Type))]
@@ -69,11 +86,21 @@ pub struct Item {
 pub struct ItemId(u32);
 
 impl ItemId {
+    pub fn new(id: u32) -> Self {
+        Self(id)
+    }
+
     pub fn inner(self) -> u32 {
         self.0
     }
 }
 
+impl Display for ItemId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
 impl SmmoModel for Item {
     const TYPE_NAME: &'static str = "Item";
 }
@@ -84,9 +111,12 @@ impl Display for Item {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize, Serialize)]
-#[cfg_attr(feature = "sql", derive(sqlx::Type))]
-#[cfg_attr(feature = "sql", sqlx(type_name = "item_type"))]
+/// The type of an item.
+///
+/// Types added to the game after this version of the crate was released are kept as
+/// [`Unknown`](Self::Unknown), with the name the api sent.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+#[non_exhaustive]
 pub enum ItemType {
     Weapon,
     Helmet,
@@ -97,12 +127,8 @@ pub enum ItemType {
     Boots,
     Special,
     Pet,
-    #[cfg_attr(feature = "sql", sqlx(rename = "Wood Axe"))]
-    #[serde(rename = "Wood Axe")]
     WoodAxe,
     Pickaxe,
-    #[cfg_attr(feature = "sql", sqlx(rename = "Fishing Rod"))]
-    #[serde(rename = "Fishing Rod")]
     FishingRod,
     Shovel,
     Material,
@@ -111,8 +137,6 @@ pub enum ItemType {
     Collectable,
     Avatar,
     Sprite,
-    #[cfg_attr(feature = "sql", sqlx(rename = "Item Sprite"))]
-    #[serde(rename = "Item Sprite")]
     ItemSprite,
     // see: item 2087
     Grenade,
@@ -120,11 +144,79 @@ pub enum ItemType {
     Background,
     // see: item 12653
     Diamonds,
-    #[cfg_attr(feature = "sql", sqlx(rename = "Event Item"))]
-    #[serde(rename = "Event Item")]
     EventItem,
+    Unknown(String),
 }
 
+impl ItemType {
+    /// The name the api uses for this type.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ItemType::Weapon => "Weapon",
+            ItemType::Helmet => "Helmet",
+            ItemType::Amulet => "Amulet",
+            ItemType::Armour => "Armour",
+            ItemType::Shield => "Shield",
+            ItemType::Greaves => "Greaves",
+            ItemType::Boots => "Boots",
+            ItemType::Special => "Special",
+            ItemType::Pet => "Pet",
+            ItemType::WoodAxe => "Wood Axe",
+            ItemType::Pickaxe => "Pickaxe",
+            ItemType::FishingRod => "Fishing Rod",
+            ItemType::Shovel => "Shovel",
+            ItemType::Material => "Material",
+            ItemType::Food => "Food",
+            ItemType::Other => "Other",
+            ItemType::Collectable => "Collectable",
+            ItemType::Avatar => "Avatar",
+            ItemType::Sprite => "Sprite",
+            ItemType::ItemSprite => "Item Sprite",
+            ItemType::Grenade => "Grenade",
+            ItemType::Book => "Book",
+            ItemType::Background => "Background",
+            ItemType::Diamonds => "Diamonds",
+            ItemType::EventItem => "Event Item",
+            ItemType::Unknown(item_type) => item_type,
+        }
+    }
+}
+
+impl From<String> for ItemType {
+    fn from(item_type: String) -> Self {
+        match item_type.as_str() {
+            "Weapon" => ItemType::Weapon,
+            "Helmet" => ItemType::Helmet,
+            "Amulet" => ItemType::Amulet,
+            "Armour" => ItemType::Armour,
+            "Shield" => ItemType::Shield,
+            "Greaves" => ItemType::Greaves,
+            "Boots" => ItemType::Boots,
+            "Special" => ItemType::Special,
+            "Pet" => ItemType::Pet,
+            "Wood Axe" => ItemType::WoodAxe,
+            "Pickaxe" => ItemType::Pickaxe,
+            "Fishing Rod" => ItemType::FishingRod,
+            "Shovel" => ItemType::Shovel,
+            "Material" => ItemType::Material,
+            "Food" => ItemType::Food,
+            "Other" => ItemType::Other,
+            "Collectable" => ItemType::Collectable,
+            "Avatar" => ItemType::Avatar,
+            "Sprite" => ItemType::Sprite,
+            "Item Sprite" => ItemType::ItemSprite,
+            "Grenade" => ItemType::Grenade,
+            "Book" => ItemType::Book,
+            "Background" => ItemType::Background,
+            "Diamonds" => ItemType::Diamonds,
+            "Event Item" => ItemType::EventItem,
+            _ => ItemType::Unknown(item_type),
+        }
+    }
+}
+
+string_enum!(ItemType, "item_type");
+
 impl Display for ItemType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(match self {
@@ -153,29 +245,197 @@ impl Display for ItemType {
             ItemType::Background => "Background",
             ItemType::Diamonds => "Diamonds",
             ItemType::EventItem => "EventItem",
+            ItemType::Unknown(item_type) => item_type,
         })
     }
 }
 
+impl ItemType {
+    /// What kind of item this is.
+    pub fn category(&self) -> ItemCategory {
+        match self {
+            ItemType::Weapon
+            | ItemType::Helmet
+            | ItemType::Amulet
+            | ItemType::Armour
+            | ItemType::Shield
+            | ItemType::Greaves
+            | ItemType::Boots
+            | ItemType::Special
+            | ItemType::Pet => ItemCategory::Equipment,
+            ItemType::WoodAxe | ItemType::Pickaxe | ItemType::FishingRod | ItemType::Shovel => {
+                ItemCategory::Tool
+            }
+            ItemType::Avatar | ItemType::Sprite | ItemType::ItemSprite | ItemType::Background => {
+                ItemCategory::Cosmetic
+            }
+            ItemType::Food | ItemType::Grenade => ItemCategory::Consumable,
+            ItemType::Material => ItemCategory::Material,
+            ItemType::Collectable => ItemCategory::Collectable,
+            ItemType::Diamonds => ItemCategory::Currency,
+            ItemType::Book | ItemType::EventItem | ItemType::Other | ItemType::Unknown(_) => {
+                ItemCategory::Other
+            }
+        }
+    }
+
+    /// The equipment slot items of this type go in, if they can be equipped.
+    pub fn slot(&self) -> Option<EquipmentSlot> {
+        match self {
+            ItemType::Weapon => Some(EquipmentSlot::Weapon),
+            ItemType::Helmet => Some(EquipmentSlot::Helmet),
+            ItemType::Amulet => Some(EquipmentSlot::Amulet),
+            ItemType::Armour => Some(EquipmentSlot::Armour),
+            ItemType::Shield => Some(EquipmentSlot::Shield),
+            ItemType::Greaves => Some(EquipmentSlot::Greaves),
+            ItemType::Boots => Some(EquipmentSlot::Boots),
+            ItemType::Special => Some(EquipmentSlot::Special),
+            ItemType::Pet => Some(EquipmentSlot::Pet),
+            ItemType::WoodAxe
+            | ItemType::Pickaxe
+            | ItemType::FishingRod
+            | ItemType::Shovel
+            | ItemType::Material
+            | ItemType::Food
+            | ItemType::Other
+            | ItemType::Collectable
+            | ItemType::Avatar
+            | ItemType::Sprite
+            | ItemType::ItemSprite
+            | ItemType::Grenade
+            | ItemType::Book
+            | ItemType::Background
+            | ItemType::Diamonds
+            | ItemType::EventItem
+            | ItemType::Unknown(_) => None,
+        }
+    }
+}
+
+/// A broad grouping of [`ItemType`]s.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize, Serialize)]
-#[cfg_attr(feature = "sql", derive(sqlx::Type))]
-#[cfg_attr(feature = "sql", sqlx(type_name = "item_rarity"))]
+#[serde(rename_all = "snake_case")]
+pub enum ItemCategory {
+    /// Items worn in one of the [`EquipmentSlot`]s.
+    Equipment,
+    /// Items used for gathering skills.
+    Tool,
+    /// Avatars, sprites and backgrounds, which only change how things look.
+    Cosmetic,
+    /// Items used up when used.
+    Consumable,
+    Material,
+    Collectable,
+    Currency,
+    Other,
+}
+
+impl Display for ItemCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ItemCategory::Equipment => "Equipment",
+            ItemCategory::Tool => "Tool",
+            ItemCategory::Cosmetic => "Cosmetic",
+            ItemCategory::Consumable => "Consumable",
+            ItemCategory::Material => "Material",
+            ItemCategory::Collectable => "Collectable",
+            ItemCategory::Currency => "Currency",
+            ItemCategory::Other => "Other",
+        })
+    }
+}
+
+/// The slots of [`PlayerEquipment`](crate::models::player_equipment::PlayerEquipment).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EquipmentSlot {
+    Weapon,
+    Helmet,
+    Amulet,
+    Armour,
+    Shield,
+    Greaves,
+    Boots,
+    Special,
+    Pet,
+}
+
+impl EquipmentSlot {
+    /// Every slot, in the order the equipment endpoint lists them.
+    pub const ALL: [EquipmentSlot; 9] = [
+        EquipmentSlot::Weapon,
+        EquipmentSlot::Helmet,
+        EquipmentSlot::Amulet,
+        EquipmentSlot::Armour,
+        EquipmentSlot::Shield,
+        EquipmentSlot::Greaves,
+        EquipmentSlot::Boots,
+        EquipmentSlot::Special,
+        EquipmentSlot::Pet,
+    ];
+}
+
+impl Display for EquipmentSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EquipmentSlot::Weapon => "Weapon",
+            EquipmentSlot::Helmet => "Helmet",
+            EquipmentSlot::Amulet => "Amulet",
+            EquipmentSlot::Armour => "Armour",
+            EquipmentSlot::Shield => "Shield",
+            EquipmentSlot::Greaves => "Greaves",
+            EquipmentSlot::Boots => "Boots",
+            EquipmentSlot::Special => "Special",
+            EquipmentSlot::Pet => "Pet",
+        })
+    }
+}
+
+/// The rarity of an item. Rarities the crate doesn't know about yet are kept as
+/// [`Unknown`](Self::Unknown).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+#[non_exhaustive]
 pub enum ItemRarity {
     Common,
     Uncommon,
     Rare,
     Epic,
-    #[serde(alias = "Elilte")]
     Elite,
-    #[serde(alias = "Lengendary")]
     Legendary,
     Exotic,
     Celestial,
+    Unknown(String),
 }
 
 impl Display for ItemRarity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(match self {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for ItemRarity {
+    fn from(rarity: String) -> Self {
+        match rarity.as_str() {
+            "Common" => ItemRarity::Common,
+            "Uncommon" => ItemRarity::Uncommon,
+            "Rare" => ItemRarity::Rare,
+            "Epic" => ItemRarity::Epic,
+            // the api has spelled these wrong in the past
+            "Elite" | "Elilte" => ItemRarity::Elite,
+            "Legendary" | "Lengendary" => ItemRarity::Legendary,
+            "Exotic" => ItemRarity::Exotic,
+            "Celestial" => ItemRarity::Celestial,
+            _ => ItemRarity::Unknown(rarity),
+        }
+    }
+}
+
+string_enum!(ItemRarity, "item_rarity");
+
+impl ItemRarity {
+    /// The name the api uses for this rarity.
+    pub fn as_str(&self) -> &str {
+        match self {
             ItemRarity::Common => "Common",
             ItemRarity::Uncommon => "Uncommon",
             ItemRarity::Rare => "Rare",
@@ -184,11 +444,11 @@ impl Display for ItemRarity {
             ItemRarity::Legendary => "Legendary",
             ItemRarity::Exotic => "Exotic",
             ItemRarity::Celestial => "Celestial",
-        })
+            ItemRarity::Unknown(rarity) => rarity,
+        }
     }
-}
 
-impl ItemRarity {
+    /// The colour the game shows this rarity in. Unknown rarities are grey.
     pub fn colour_hex(&self) -> u32 {
         match self {
             ItemRarity::Common => 0x34495E,
@@ -199,6 +459,7 @@ impl ItemRarity {
             ItemRarity::Legendary => 0xF1C40F,
             ItemRarity::Exotic => 0x27AE60,
             ItemRarity::Celestial => 0x00F6FF,
+            ItemRarity::Unknown(_) => 0x95A5A6,
         }
     }
 
@@ -213,46 +474,70 @@ impl ItemRarity {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize, Serialize)]
-#[cfg_attr(feature = "sql", derive(sqlx::Type))]
-#[cfg_attr(feature = "sql", sqlx(rename_all = "lowercase"))]
-#[cfg_attr(feature = "sql", sqlx(type_name = "item_stat"))]
-#[serde(rename_all = "lowercase")]
+/// A stat an item can give a bonus to. Stats the crate doesn't know about yet are kept as
+/// [`Unknown`](Self::Unknown).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+#[non_exhaustive]
 pub enum ItemStat {
     Str,
     Def,
     Dex,
     Crit,
     Hp,
+    Unknown(String),
+}
+
+impl ItemStat {
+    /// The name the api uses for this stat.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ItemStat::Str => "str",
+            ItemStat::Def => "def",
+            ItemStat::Dex => "dex",
+            ItemStat::Crit => "crit",
+            ItemStat::Hp => "hp",
+            ItemStat::Unknown(stat) => stat,
+        }
+    }
+}
+
+impl From<String> for ItemStat {
+    fn from(stat: String) -> Self {
+        match stat.as_str() {
+            "str" => ItemStat::Str,
+            "def" => ItemStat::Def,
+            "dex" => ItemStat::Dex,
+            "crit" => ItemStat::Crit,
+            "hp" => ItemStat::Hp,
+            _ => ItemStat::Unknown(stat),
+        }
+    }
+}
+
+string_enum!(ItemStat, "item_stat");
+
+impl Display for ItemStat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ItemStat::Str => "Str",
+            ItemStat::Def => "Def",
+            ItemStat::Dex => "Dex",
+            ItemStat::Crit => "Crit",
+            ItemStat::Hp => "Hp",
+            ItemStat::Unknown(stat) => stat,
+        })
+    }
 }
 
 #[cfg(test)]
 mod test_item_deserialize {
     use super::*;
+    use crate::{models::stats::StatSlots, test_fixtures::ITEM};
 
     #[test]
     fn test_item() {
-        let json = r#"{
-            "id": 1,
-            "name": "Wooden Stick",
-            "type": "Weapon",
-            "description": "",
-            "equipable": "1",
-            "level": 1,
-            "rarity": "Common",
-            "value": 20,
-            "stat1": "str",
-            "stat1modifier": 1,
-            "stat2": null,
-            "stat2modifier": 0,
-            "stat3": null,
-            "stat3modifier": null,
-            "custom_item": 0,
-            "tradable": 1,
-            "locked": 0
-        }"#;
         assert_eq!(
-            serde_json::from_str::<Item>(json).unwrap(),
+            serde_json::from_str::<Item>(ITEM).unwrap(),
             Item {
                 id: ItemId(1),
                 name: "Wooden Stick".to_string(),
@@ -263,18 +548,44 @@ mod test_item_deserialize {
                 rarity: ItemRarity::Common,
                 value: 20,
                 stat1: Some(ItemStat::Str),
-                stat1modifier: 1,
+                stat1modifier: Some(1),
                 stat2: None,
-                stat2modifier: 0,
+                stat2modifier: Some(0),
                 stat3: None,
-                stat3modifier: 0,
+                stat3modifier: None,
                 custom_item: false,
                 tradable: true,
-                locked: false
+                locked: false,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
             }
         )
     }
 
+    #[test]
+    fn test_item_stats() {
+        let json = ITEM
+            .replace(r#""stat2": null"#, r#""stat2": "def""#)
+            .replace(r#""stat2modifier": 0"#, r#""stat2modifier": null"#)
+            .replace(r#""stat3": null"#, r#""stat3": "str""#)
+            .replace(r#""stat3modifier": null"#, r#""stat3modifier": 4"#);
+        let item = serde_json::from_str::<Item>(&json).unwrap();
+        assert_eq!(
+            item.stats().iter().collect::<Vec<_>>(),
+            vec![(ItemStat::Str, 5)]
+        );
+        assert_eq!(item.stats_missing_modifier(), vec![ItemStat::Def]);
+    }
+
+    #[test]
+    fn test_item_round_trip() {
+        let item = serde_json::from_str::<Item>(ITEM).unwrap();
+        assert_eq!(
+            serde_json::to_value(&item).unwrap(),
+            serde_json::from_str::<serde_json::Value>(ITEM).unwrap()
+        );
+    }
+
     #[test]
     fn test_item_type() {
         let json = r#""Weapon""#;
@@ -284,6 +595,18 @@ mod test_item_deserialize {
         )
     }
 
+    #[test]
+    fn test_item_type_category_and_slot() {
+        assert_eq!(ItemType::Boots.category(), ItemCategory::Equipment);
+        assert_eq!(ItemType::Boots.slot(), Some(EquipmentSlot::Boots));
+        assert_eq!(ItemType::Pet.slot(), Some(EquipmentSlot::Pet));
+        assert_eq!(ItemType::FishingRod.category(), ItemCategory::Tool);
+        assert_eq!(ItemType::FishingRod.slot(), None);
+        assert_eq!(ItemType::ItemSprite.category(), ItemCategory::Cosmetic);
+        assert_eq!(ItemType::Grenade.category(), ItemCategory::Consumable);
+        assert_eq!(ItemType::Diamonds.category(), ItemCategory::Currency);
+    }
+
     #[test]
     fn test_item_type_ser() {
         let json = r#""Weapon""#;
@@ -292,4 +615,35 @@ mod test_item_deserialize {
             json
         )
     }
+
+    #[test]
+    fn test_renamed_variants() {
+        assert_eq!(
+            serde_json::from_str::<ItemType>(r#""Fishing Rod""#).unwrap(),
+            ItemType::FishingRod
+        );
+        assert_eq!(
+            serde_json::to_string(&ItemType::FishingRod).unwrap(),
+            r#""Fishing Rod""#
+        );
+        assert_eq!(
+            serde_json::from_str::<ItemRarity>(r#""Lengendary""#).unwrap(),
+            ItemRarity::Legendary
+        );
+        assert_eq!(serde_json::to_string(&ItemStat::Crit).unwrap(), r#""crit""#);
+    }
+
+    #[test]
+    fn test_unknown_variants() {
+        let json = r#"{"id":20000,"name":"Mystery Box","type":"Lootbox","description":"","equipable":"0","level":1,"rarity":"Mythic","value":20,"stat1":"luck","stat1modifier":5,"stat2":null,"stat2modifier":null,"stat3":null,"stat3modifier":null,"custom_item":0,"tradable":1,"locked":0}"#;
+        let item = serde_json::from_str::<Item>(json).unwrap();
+
+        assert_eq!(item.item_type, ItemType::Unknown("Lootbox".into()));
+        assert_eq!(item.item_type.to_string(), "Lootbox");
+        assert_eq!(item.item_type.category(), ItemCategory::Other);
+        assert_eq!(item.rarity, ItemRarity::Unknown("Mythic".into()));
+        assert_eq!(item.rarity.colour_rgb(), (0x95, 0xA5, 0xA6));
+        assert_eq!(item.stat1, Some(ItemStat::Unknown("luck".into())));
+        assert_eq!(serde_json::to_string(&item).unwrap(), json);
+    }
 }
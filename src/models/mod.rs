@@ -1,14 +1,116 @@
 use std::fmt::Display;
 
-use serde::{Serialize, de::DeserializeOwned};
+use serde::{de::DeserializeOwned, Serialize};
 
+/// (De)serializes an enum with an `as_str` and a `From<String>` as the api name of the variant,
+/// both in json and in the database. Unknown names are kept rather than rejected (unless decoding
+/// in [`DecodeMode::Strict`](crate::decode::DecodeMode::Strict)), so a new variant in the game
+/// doesn't break decoding.
+macro_rules! string_enum {
+    ($enum:ident, $sql_type_name:literal) => {
+        impl serde::Serialize for $enum {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $enum {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = $enum::from(<String as serde::Deserialize>::deserialize(deserializer)?);
+                if let $enum::Unknown(name) = &value {
+                    $crate::decode::report($crate::decode::DecodeWarningKind::UnknownVariant {
+                        type_name: stringify!($enum),
+                        value: name.clone(),
+                    })
+                    .map_err(serde::de::Error::custom)?;
+                }
+                Ok(value)
+            }
+        }
+
+        #[cfg(feature = "sql")]
+        impl sqlx::Type<sqlx::Postgres> for $enum {
+            fn type_info() -> sqlx::postgres::PgTypeInfo {
+                sqlx::postgres::PgTypeInfo::with_name($sql_type_name)
+            }
+        }
+
+        #[cfg(feature = "sql")]
+        impl<'q> sqlx::Encode<'q, sqlx::Postgres> for $enum {
+            fn encode_by_ref(
+                &self,
+                buf: &mut sqlx::postgres::PgArgumentBuffer,
+            ) -> sqlx::encode::IsNull {
+                <&str as sqlx::Encode<'q, sqlx::Postgres>>::encode_by_ref(&self.as_str(), buf)
+            }
+        }
+
+        #[cfg(feature = "sql")]
+        impl<'r> sqlx::Decode<'r, sqlx::Postgres> for $enum {
+            fn decode(
+                value: sqlx::postgres::PgValueRef<'r>,
+            ) -> Result<Self, sqlx::error::BoxDynError> {
+                <&str as sqlx::Decode<'r, sqlx::Postgres>>::decode(value)
+                    .map(|name| $enum::from(name.to_string()))
+            }
+        }
+    };
+}
+
+/// Implements [`StatSlots`](crate::models::stats::StatSlots) for a model with `stat1`..`stat3`
+/// and matching modifier fields, and (de)serializes it with the `Serialize` and `Deserialize`
+/// derived by `#[serde(remote = "Self")]`, reporting every stat decoded without a modifier.
+macro_rules! stat_slots {
+    ($model:ident) => {
+        impl $crate::models::stats::StatSlots for $model {
+            fn stat_slots(&self) -> [(&Option<$crate::models::item::ItemStat>, Option<u32>); 3] {
+                [
+                    (&self.stat1, self.stat1modifier),
+                    (&self.stat2, self.stat2modifier),
+                    (&self.stat3, self.stat3modifier),
+                ]
+            }
+        }
+
+        impl serde::Serialize for $model {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $model::serialize(self, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $model {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let model = $model::deserialize(deserializer)?;
+                $crate::models::stats::report_missing_modifiers(&model)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(model)
+            }
+        }
+    };
+}
+
+pub mod diamond_market;
+#[cfg(feature = "extra-fields")]
+pub mod extra;
+pub mod guild;
+pub mod inventory;
 pub mod item;
 pub mod orphanage;
+pub mod pet;
+pub mod player_equipment;
+pub mod player_skills;
 pub mod smmo_player;
+pub mod stats;
+#[cfg(test)]
+mod test_round_trip;
 pub mod world_boss;
 
 pub trait SmmoModel: Display + Serialize + DeserializeOwned {
     const TYPE_NAME: &'static str;
+
+    /// The fields of the model's object that are `#[serde(default)]`, and so may be left out of
+    /// a response without being reported as missing.
+    const OPTIONAL_FIELDS: &'static [&'static str] = &[];
 }
 
 // impl<T: SmmoModel> SmmoModel for Vec<T> {
@@ -3,20 +3,44 @@ use std::fmt::{self, Display};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "extra-fields")]
+use crate::models::extra::{ExtraFields, UnmappedFields};
 use crate::models::{smmo_player::UserId, SmmoModel};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Orphanage {
     pub current_amount: u64,
     pub max_amount: u64,
     pub recent_donators: Vec<RecentDonator>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for Orphanage {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+        self.recent_donators
+            .collect_unmapped_fields(&format!("{}.recent_donators", path), fields);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RecentDonator {
     pub user_id: UserId,
     pub amount: u64,
     pub created_at: DateTime<Utc>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for RecentDonator {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
 }
 
 impl SmmoModel for Orphanage {
@@ -0,0 +1,114 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "extra-fields")]
+use crate::models::extra::{ExtraFields, UnmappedFields};
+use crate::{
+    custom_serde::ok_or_default,
+    models::{
+        item::{ItemId, ItemStat},
+        SmmoModel,
+    },
+};
+
+/// A pet owned by a player. Pets level up separately from the [`Item`](crate::models::item::Item)
+/// they came from, and give bonus stats in the same way as equipment.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(remote = "Self")]
+pub struct Pet {
+    /// The [`ItemType::Pet`](crate::models::item::ItemType::Pet) item this pet is.
+    pub item_id: ItemId,
+
+    pub name: String,
+
+    pub level: u32,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat1: Option<ItemStat>,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat1modifier: Option<u32>,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat2: Option<ItemStat>,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat2modifier: Option<u32>,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat3: Option<ItemStat>,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat3modifier: Option<u32>,
+
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for Pet {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
+}
+
+stat_slots!(Pet);
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PlayerPets(pub Vec<Pet>);
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for PlayerPets {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.0.collect_unmapped_fields(path, fields);
+    }
+}
+
+impl SmmoModel for PlayerPets {
+    const TYPE_NAME: &'static str = "Vec<Pet>";
+}
+
+impl Display for PlayerPets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string_pretty(&self).map_err(|_| fmt::Error)?)
+    }
+}
+
+#[cfg(test)]
+mod test_pet_deserialize {
+    use super::*;
+
+    #[test]
+    fn test_player_pets() {
+        let json = r#"[{
+            "item_id": 1500,
+            "name": "Baby Dragon",
+            "level": 12,
+            "stat1": "str",
+            "stat1modifier": 25,
+            "stat2": "hp",
+            "stat2modifier": 100,
+            "stat3": null,
+            "stat3modifier": null
+        }]"#;
+
+        assert_eq!(
+            serde_json::from_str::<PlayerPets>(json).unwrap(),
+            PlayerPets(vec![Pet {
+                item_id: ItemId::new(1500),
+                name: "Baby Dragon".into(),
+                level: 12,
+                stat1: Some(ItemStat::Str),
+                stat1modifier: Some(25),
+                stat2: Some(ItemStat::Hp),
+                stat2modifier: Some(100),
+                stat3: None,
+                stat3modifier: None,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            }])
+        );
+    }
+}
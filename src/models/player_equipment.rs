@@ -0,0 +1,178 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "extra-fields")]
+use crate::models::extra::{ExtraFields, UnmappedFields};
+use crate::{
+    custom_serde::ok_or_default,
+    models::{
+        item::{EquipmentSlot, ItemId, ItemRarity, ItemStat, ItemType},
+        stats::{ItemStats, StatSlots},
+        SmmoModel,
+    },
+};
+
+/// The items a player currently has equipped, by slot. Empty slots are `None`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PlayerEquipment {
+    #[serde(default)]
+    pub weapon: Option<EquippedItem>,
+    #[serde(default)]
+    pub helmet: Option<EquippedItem>,
+    #[serde(default)]
+    pub amulet: Option<EquippedItem>,
+    #[serde(default)]
+    pub armour: Option<EquippedItem>,
+    #[serde(default)]
+    pub shield: Option<EquippedItem>,
+    #[serde(default)]
+    pub greaves: Option<EquippedItem>,
+    #[serde(default)]
+    pub boots: Option<EquippedItem>,
+    #[serde(default)]
+    pub special: Option<EquippedItem>,
+    #[serde(default)]
+    pub pet: Option<EquippedItem>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for PlayerEquipment {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+        for (name, item) in [
+            ("weapon", &self.weapon),
+            ("helmet", &self.helmet),
+            ("amulet", &self.amulet),
+            ("armour", &self.armour),
+            ("shield", &self.shield),
+            ("greaves", &self.greaves),
+            ("boots", &self.boots),
+            ("special", &self.special),
+            ("pet", &self.pet),
+        ] {
+            item.collect_unmapped_fields(&format!("{}.{}", path, name), fields);
+        }
+    }
+}
+
+impl PlayerEquipment {
+    /// All equipped items, in slot order.
+    pub fn items(&self) -> impl Iterator<Item = &EquippedItem> {
+        EquipmentSlot::ALL
+            .iter()
+            .filter_map(move |slot| self.get(*slot))
+    }
+
+    /// The item equipped in `slot`.
+    pub fn get(&self, slot: EquipmentSlot) -> Option<&EquippedItem> {
+        match slot {
+            EquipmentSlot::Weapon => self.weapon.as_ref(),
+            EquipmentSlot::Helmet => self.helmet.as_ref(),
+            EquipmentSlot::Amulet => self.amulet.as_ref(),
+            EquipmentSlot::Armour => self.armour.as_ref(),
+            EquipmentSlot::Shield => self.shield.as_ref(),
+            EquipmentSlot::Greaves => self.greaves.as_ref(),
+            EquipmentSlot::Boots => self.boots.as_ref(),
+            EquipmentSlot::Special => self.special.as_ref(),
+            EquipmentSlot::Pet => self.pet.as_ref(),
+        }
+    }
+
+    /// The combined stat bonuses of all equipped items.
+    pub fn total_stats(&self) -> ItemStats {
+        self.items().map(EquippedItem::stats).sum()
+    }
+}
+
+/// An equipped item. These are the fields of an [`Item`](crate::models::item::Item) that the
+/// equipment endpoint includes; fetch the item by its id for the rest.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(remote = "Self")]
+pub struct EquippedItem {
+    pub id: ItemId,
+
+    pub name: String,
+
+    #[serde(rename = "type")]
+    pub item_type: ItemType,
+
+    pub level: u32,
+
+    pub rarity: ItemRarity,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat1: Option<ItemStat>,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat1modifier: Option<u32>,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat2: Option<ItemStat>,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat2modifier: Option<u32>,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat3: Option<ItemStat>,
+
+    #[serde(deserialize_with = "ok_or_default::deserialize")]
+    pub stat3modifier: Option<u32>,
+
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for EquippedItem {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
+}
+
+stat_slots!(EquippedItem);
+
+impl SmmoModel for PlayerEquipment {
+    const TYPE_NAME: &'static str = "PlayerEquipment";
+
+    // the api leaves out empty slots
+    const OPTIONAL_FIELDS: &'static [&'static str] = &[
+        "weapon", "helmet", "amulet", "armour", "shield", "greaves", "boots", "special", "pet",
+    ];
+}
+
+impl Display for PlayerEquipment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string_pretty(&self).map_err(|_| fmt::Error)?)
+    }
+}
+
+#[cfg(test)]
+mod test_player_equipment_deserialize {
+    use super::*;
+    use crate::test_fixtures::PLAYER_EQUIPMENT;
+
+    #[test]
+    fn test_player_equipment() {
+        let equipment = serde_json::from_str::<PlayerEquipment>(PLAYER_EQUIPMENT).unwrap();
+        let weapon = equipment.weapon.as_ref().unwrap();
+        assert_eq!(weapon.id.inner(), 1);
+        assert_eq!(weapon.item_type, ItemType::Weapon);
+        assert_eq!(weapon.rarity, ItemRarity::Common);
+        assert_eq!(weapon.stat1, Some(ItemStat::Str));
+        assert_eq!(weapon.stat1modifier, Some(1));
+        assert_eq!(weapon.stat3modifier, None);
+        assert_eq!(equipment.helmet, None);
+        assert_eq!(equipment.items().count(), 2);
+        assert_eq!(
+            equipment.get(EquipmentSlot::Pet).unwrap().name,
+            "Baby Dragon"
+        );
+        assert_eq!(equipment.get(EquipmentSlot::Boots), None);
+        assert_eq!(equipment.total_stats().to_string(), "Str +26, Hp +100");
+    }
+}
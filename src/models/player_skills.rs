@@ -0,0 +1,161 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "extra-fields")]
+use crate::models::extra::{ExtraFields, UnmappedFields};
+use crate::models::SmmoModel;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PlayerSkills(pub Vec<PlayerSkill>);
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for PlayerSkills {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.0.collect_unmapped_fields(path, fields);
+    }
+}
+
+impl PlayerSkills {
+    pub fn get(&self, skill: &Skill) -> Option<&PlayerSkill> {
+        self.0
+            .iter()
+            .find(|player_skill| &player_skill.skill == skill)
+    }
+}
+
+impl SmmoModel for PlayerSkills {
+    const TYPE_NAME: &'static str = "Vec<PlayerSkill>";
+}
+
+impl Display for PlayerSkills {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string_pretty(&self).map_err(|_| fmt::Error)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PlayerSkill {
+    pub skill: Skill,
+    pub level: u32,
+    pub exp: u64,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for PlayerSkill {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
+}
+
+/// A skill players can level up. Skills the crate doesn't know about yet are kept as
+/// [`Unknown`](Self::Unknown).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+#[non_exhaustive]
+pub enum Skill {
+    Mining,
+    Woodcutting,
+    Fishing,
+    Crafting,
+    TreasureHunting,
+    Unknown(String),
+}
+
+impl Skill {
+    /// The name the api uses for this skill.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Skill::Mining => "mining",
+            Skill::Woodcutting => "woodcutting",
+            Skill::Fishing => "fishing",
+            Skill::Crafting => "crafting",
+            Skill::TreasureHunting => "treasure_hunting",
+            Skill::Unknown(skill) => skill,
+        }
+    }
+}
+
+impl From<String> for Skill {
+    fn from(skill: String) -> Self {
+        match skill.as_str() {
+            "mining" => Skill::Mining,
+            "woodcutting" => Skill::Woodcutting,
+            "fishing" => Skill::Fishing,
+            "crafting" => Skill::Crafting,
+            "treasure_hunting" => Skill::TreasureHunting,
+            _ => Skill::Unknown(skill),
+        }
+    }
+}
+
+string_enum!(Skill, "skill");
+
+impl Display for Skill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Skill::Mining => "Mining",
+            Skill::Woodcutting => "Woodcutting",
+            Skill::Fishing => "Fishing",
+            Skill::Crafting => "Crafting",
+            Skill::TreasureHunting => "Treasure Hunting",
+            Skill::Unknown(skill) => skill,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_player_skills_deserialize {
+    use super::*;
+    use crate::decode::{decode, DecodeMode, DecodeWarningKind};
+
+    #[test]
+    fn test_player_skills() {
+        let json = r#"[
+            { "skill": "mining", "level": 12, "exp": 4520 },
+            { "skill": "treasure_hunting", "level": 3, "exp": 210 }
+        ]"#;
+
+        let skills = serde_json::from_str::<PlayerSkills>(json).unwrap();
+        assert_eq!(
+            skills.get(&Skill::TreasureHunting),
+            Some(&PlayerSkill {
+                skill: Skill::TreasureHunting,
+                level: 3,
+                exp: 210,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            })
+        );
+        assert_eq!(skills.get(&Skill::Mining).unwrap().level, 12);
+        assert_eq!(skills.get(&Skill::Fishing), None);
+    }
+
+    #[test]
+    fn test_unknown_skill() {
+        let json = r#"[{ "skill": "smithing", "level": 5, "exp": 800 }]"#;
+
+        let skills = serde_json::from_str::<PlayerSkills>(json).unwrap();
+        let smithing = Skill::Unknown("smithing".into());
+        assert_eq!(skills.get(&smithing).unwrap().level, 5);
+        assert_eq!(smithing.to_string(), "smithing");
+        assert_eq!(
+            serde_json::to_string(&skills).unwrap(),
+            json.replace(' ', "")
+        );
+
+        let (_, warnings) = decode::<PlayerSkills>(json, DecodeMode::Lenient).unwrap();
+        let warning = warnings.iter().next().unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warning.path, "$[0].skill");
+        assert_eq!(
+            warning.kind,
+            DecodeWarningKind::UnknownVariant {
+                type_name: "Skill",
+                value: "smithing".into()
+            }
+        );
+    }
+}
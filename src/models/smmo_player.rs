@@ -1,13 +1,18 @@
-use std::fmt::{self, Display};
+use std::{
+    fmt::{self, Display},
+    ops::Deref,
+};
 
+#[cfg(feature = "extra-fields")]
+use crate::models::extra::{ExtraFields, UnmappedFields};
 use crate::{
     custom_serde::{bool_from_int, date_time_option::*},
-    models::SmmoModel,
+    models::{guild::GuildId, SmmoModel},
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SmmoPlayer {
     pub id: UserId,
     pub name: String,
@@ -29,14 +34,27 @@ pub struct SmmoPlayer {
     pub hp: u32,
     pub max_hp: u32,
     #[serde(rename = "safeMode")]
-    #[serde(deserialize_with = "bool_from_int::deserialize")]
+    #[serde(with = "bool_from_int")]
     pub safe_mode: bool,
     #[serde(rename = "safeModeTime")]
     #[serde(deserialize_with = "deserialize_option_datefmt")]
+    #[serde(serialize_with = "serialize_option_datefmt")]
     pub safe_mode_time: Option<DateTime<Utc>>,
     pub background: u32,
     pub membership: u32,
     pub guild: Option<SmmoPlayerGuild>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for SmmoPlayer {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+        self.guild
+            .collect_unmapped_fields(&format!("{}.guild", path), fields);
+    }
 }
 
 impl SmmoModel for SmmoPlayer {
@@ -49,11 +67,104 @@ impl Display for SmmoPlayer {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// The owner of the api key, with the private information only they can see.
+///
+/// Derefs to the public [`SmmoPlayer`] fields.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelfPlayer {
+    #[serde(flatten)]
+    pub player: SmmoPlayer,
+    pub energy: u32,
+    pub max_energy: u32,
+    pub quest_points: u32,
+    pub max_quest_points: u32,
+    /// Gold in the bank, on top of the gold carried.
+    pub bank: u64,
+    pub diamonds: u32,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for SelfPlayer {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.player.collect_unmapped_fields(path, fields);
+    }
+}
+
+impl Deref for SelfPlayer {
+    type Target = SmmoPlayer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.player
+    }
+}
+
+impl SmmoModel for SelfPlayer {
+    const TYPE_NAME: &'static str = "SelfPlayer";
+}
+
+impl Display for SelfPlayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string_pretty(&self).map_err(|_| fmt::Error)?)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "sql", derive(sqlx::Type))]
+#[cfg_attr(feature = "sql", sqlx(transparent))]
 pub struct UserId(u32);
 
-#[derive(Debug, Serialize, Deserialize)]
+impl UserId {
+    pub fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    pub fn inner(self) -> u32 {
+        self.0
+    }
+}
+
+impl Display for UserId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SmmoPlayerGuild {
-    pub id: u32,
+    /// Pass to [`SmmoClient::get_guild`](crate::client::SmmoClient::get_guild) for the rest of
+    /// the guild's information.
+    pub id: GuildId,
     pub name: String,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for SmmoPlayerGuild {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
+}
+
+#[cfg(test)]
+mod test_smmo_player_deserialize {
+    use super::*;
+    use crate::test_fixtures::SELF_PLAYER;
+
+    #[test]
+    fn test_self_player() {
+        let me = serde_json::from_str::<SelfPlayer>(SELF_PLAYER).unwrap();
+        assert_eq!(me.id, UserId(1234));
+        assert!(me.safe_mode);
+        assert_eq!(me.guild.as_ref().unwrap().id, GuildId::new(7));
+        assert_eq!(me.energy, 8);
+        assert_eq!(me.bank, 1000000);
+        assert_eq!(me.diamonds, 25);
+
+        assert_eq!(
+            serde_json::to_value(&me).unwrap(),
+            serde_json::from_str::<serde_json::Value>(SELF_PLAYER).unwrap()
+        );
+    }
 }
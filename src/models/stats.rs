@@ -0,0 +1,199 @@
+use std::{
+    collections::{btree_map, BTreeMap},
+    fmt::{self, Display},
+    iter::{FromIterator, Sum},
+    ops::{Add, AddAssign},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decode::{self, DecodeWarningKind},
+    models::item::ItemStat,
+};
+
+/// Stat bonuses, by stat. Bonuses to the same stat add up, so the stats of several items can be
+/// summed into one total:
+///
+/// ```
+/// # use smmo_api::models::stats::{ItemStats, StatSlots};
+/// # fn total(equipment: &smmo_api::models::player_equipment::PlayerEquipment) -> ItemStats {
+/// equipment.items().map(|item| item.stats()).sum()
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ItemStats(BTreeMap<ItemStat, u32>);
+
+impl ItemStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The bonus to `stat`, or 0 if there is none.
+    pub fn get(&self, stat: &ItemStat) -> u32 {
+        self.0.get(stat).copied().unwrap_or_default()
+    }
+
+    pub fn add_stat(&mut self, stat: ItemStat, modifier: u32) {
+        let total = self.0.entry(stat).or_default();
+        *total = total.saturating_add(modifier);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ItemStat, u32)> + '_ {
+        self.0
+            .iter()
+            .map(|(stat, modifier)| (stat.clone(), *modifier))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// A model with `stat1`..`stat3` and matching modifier fields, such as an
+/// [`Item`](crate::models::item::Item). Decoding one reports every stat without a modifier as a
+/// [`DecodeWarningKind::MissingModifier`].
+pub trait StatSlots {
+    /// The stat and modifier in each of the three slots, in order.
+    fn stat_slots(&self) -> [(&Option<ItemStat>, Option<u32>); 3];
+
+    /// The stat bonuses this gives. Stats the api sent without a modifier are left out; see
+    /// [`stats_missing_modifier`](Self::stats_missing_modifier).
+    fn stats(&self) -> ItemStats {
+        self.stat_slots()
+            .iter()
+            .filter_map(|(stat, modifier)| Some(((*stat).clone()?, (*modifier)?)))
+            .collect()
+    }
+
+    /// Stats the api sent without a (valid) modifier, so their bonus is unknown rather than 0.
+    fn stats_missing_modifier(&self) -> Vec<ItemStat> {
+        self.stat_slots()
+            .iter()
+            .filter(|(_, modifier)| modifier.is_none())
+            .filter_map(|(stat, _)| (*stat).clone())
+            .collect()
+    }
+}
+
+/// Reports each stat of a just decoded `model` that has no modifier, at its modifier field.
+pub(crate) fn report_missing_modifiers(model: &impl StatSlots) -> Result<(), String> {
+    for (slot, (stat, modifier)) in model.stat_slots().iter().enumerate() {
+        if let (Some(stat), None) = (stat, modifier) {
+            decode::report_field(
+                &format!("stat{}modifier", slot + 1),
+                DecodeWarningKind::MissingModifier { stat: stat.clone() },
+            )?;
+        }
+    }
+    Ok(())
+}
+
+impl Add for ItemStats {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for ItemStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.extend(rhs);
+    }
+}
+
+impl Sum for ItemStats {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(), Add::add)
+    }
+}
+
+impl Extend<(ItemStat, u32)> for ItemStats {
+    fn extend<I: IntoIterator<Item = (ItemStat, u32)>>(&mut self, iter: I) {
+        for (stat, modifier) in iter {
+            self.add_stat(stat, modifier);
+        }
+    }
+}
+
+impl FromIterator<(ItemStat, u32)> for ItemStats {
+    fn from_iter<I: IntoIterator<Item = (ItemStat, u32)>>(iter: I) -> Self {
+        let mut stats = Self::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+impl IntoIterator for ItemStats {
+    type Item = (ItemStat, u32);
+    type IntoIter = btree_map::IntoIter<ItemStat, u32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// e.g. `Str +5, Def +2`, or `none` if there are no stats.
+impl Display for ItemStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("none");
+        }
+        let stats = self
+            .iter()
+            .map(|(stat, modifier)| format!("{} +{}", stat, modifier))
+            .collect::<Vec<_>>();
+        f.write_str(&stats.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test_item_stats {
+    use super::*;
+
+    struct Slots(Option<ItemStat>, Option<ItemStat>);
+
+    impl StatSlots for Slots {
+        fn stat_slots(&self) -> [(&Option<ItemStat>, Option<u32>); 3] {
+            [(&self.0, Some(2)), (&self.1, None), (&self.0, Some(3))]
+        }
+    }
+
+    #[test]
+    fn test_stat_slots() {
+        let slots = Slots(Some(ItemStat::Str), Some(ItemStat::Def));
+
+        let stats = slots.stats();
+        assert_eq!(stats.iter().collect::<Vec<_>>(), vec![(ItemStat::Str, 5)]);
+        assert_eq!(stats.get(&ItemStat::Def), 0);
+        assert_eq!(slots.stats_missing_modifier(), vec![ItemStat::Def]);
+    }
+
+    #[test]
+    fn test_saturates() {
+        let stats = vec![(ItemStat::Hp, u32::MAX), (ItemStat::Hp, 10)]
+            .into_iter()
+            .collect::<ItemStats>();
+        assert_eq!(stats.get(&ItemStat::Hp), u32::MAX);
+    }
+
+    #[test]
+    fn test_sum() {
+        let total = vec![
+            vec![(ItemStat::Str, 1), (ItemStat::Hp, 10)]
+                .into_iter()
+                .collect::<ItemStats>(),
+            vec![(ItemStat::Str, 2)].into_iter().collect(),
+            ItemStats::new(),
+        ]
+        .into_iter()
+        .sum::<ItemStats>();
+
+        assert_eq!(total.get(&ItemStat::Str), 3);
+        assert_eq!(total.get(&ItemStat::Hp), 10);
+        assert_eq!(total.to_string(), "Str +3, Hp +10");
+        assert_eq!(ItemStats::new().to_string(), "none");
+    }
+}
@@ -0,0 +1,655 @@
+//! Every model must decode from what it serializes to, unchanged, so that models can be cached as
+//! json in the api's own format.
+
+use std::fmt::Debug;
+
+use chrono::{DateTime, TimeZone, Utc};
+use proptest::{prelude::*, sample::select, test_runner::TestCaseError};
+#[cfg(feature = "extra-fields")]
+use serde_json::Value;
+
+#[cfg(feature = "extra-fields")]
+use crate::models::extra::ExtraFields;
+use crate::{
+    decode::{self, DecodeMode, DecodeWarningKind},
+    models::{
+        diamond_market::{DiamondListing, DiamondMarket},
+        guild::{
+            Guild, GuildId, GuildMember, GuildMembers, GuildWar, GuildWarSide, GuildWars, WarStatus,
+        },
+        inventory::{Inventory, InventoryEntry},
+        item::{Item, ItemId, ItemRarity, ItemStat, ItemType},
+        orphanage::{Orphanage, RecentDonator},
+        pet::{Pet, PlayerPets},
+        player_equipment::{EquippedItem, PlayerEquipment},
+        player_skills::{PlayerSkill, PlayerSkills, Skill},
+        smmo_player::{SelfPlayer, SmmoPlayer, SmmoPlayerGuild, UserId},
+        world_boss::{WorldBoss, WorldBosses},
+        SmmoModel,
+    },
+};
+
+/// Serializes `model`, decodes the json again and checks that both the model and the json
+/// survived.
+fn assert_round_trips<T>(model: &T) -> Result<(), TestCaseError>
+where
+    T: SmmoModel + PartialEq + Debug,
+{
+    let json = serde_json::to_value(model).map_err(|why| TestCaseError::fail(why.to_string()))?;
+    let (decoded, warnings) = decode::decode_value::<T>(json.clone(), DecodeMode::Lenient)
+        .map_err(|why| TestCaseError::fail(format!("{} in {}", why, json)))?;
+    // unknown variants, stats without a modifier and extra fields are kept, but still reported
+    prop_assert!(
+        warnings.iter().all(|warning| match warning.kind {
+            DecodeWarningKind::UnknownVariant { .. }
+            | DecodeWarningKind::MissingModifier { .. } => true,
+            DecodeWarningKind::UnknownField => warning
+                .path
+                .rsplit('.')
+                .next()
+                .is_some_and(|field| field.starts_with(EXTRA_FIELD_PREFIX)),
+            _ => false,
+        }),
+        "{} in {}",
+        warnings,
+        json
+    );
+    prop_assert_eq!(&decoded, model);
+    prop_assert_eq!(serde_json::to_value(&decoded).unwrap(), json);
+    Ok(())
+}
+
+/// Starts the name of every generated extra field, so that none of them collide with a field of
+/// a model.
+const EXTRA_FIELD_PREFIX: &str = "x_";
+
+/// Models with their `extra` fields, which only exist with the `extra-fields` feature.
+#[cfg(feature = "extra-fields")]
+trait HasExtra {
+    fn extra_mut(&mut self) -> &mut ExtraFields;
+}
+
+#[cfg(feature = "extra-fields")]
+macro_rules! has_extra {
+    ($($model:ty),* $(,)?) => {
+        $(
+            impl HasExtra for $model {
+                fn extra_mut(&mut self) -> &mut ExtraFields {
+                    &mut self.extra
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "extra-fields")]
+has_extra!(
+    Item,
+    EquippedItem,
+    PlayerEquipment,
+    Pet,
+    PlayerSkill,
+    Guild,
+    GuildMember,
+    GuildWarSide,
+    GuildWar,
+    InventoryEntry,
+    DiamondListing,
+    RecentDonator,
+    Orphanage,
+    SmmoPlayerGuild,
+    SmmoPlayer,
+    WorldBoss,
+);
+
+/// Fills in the model's extra fields, so that they are round tripped through `#[serde(flatten)]`
+/// too.
+#[cfg(feature = "extra-fields")]
+fn with_extra<T: HasExtra + Debug>(model: impl Strategy<Value = T>) -> impl Strategy<Value = T> {
+    let value = prop_oneof![
+        any::<i64>().prop_map(Value::from),
+        any::<String>().prop_map(Value::from),
+        any::<bool>().prop_map(Value::from),
+        Just(Value::Null),
+    ];
+    let extra = proptest::collection::btree_map(
+        "[a-z]{1,8}".prop_map(|name| format!("{}{}", EXTRA_FIELD_PREFIX, name)),
+        value,
+        1..3,
+    );
+    (model, extra).prop_map(|(mut model, extra)| {
+        *model.extra_mut() = ExtraFields(extra);
+        model
+    })
+}
+
+#[cfg(not(feature = "extra-fields"))]
+fn with_extra<T: Debug>(model: impl Strategy<Value = T>) -> impl Strategy<Value = T> {
+    model
+}
+
+/// A known name, so that it decodes to its variant, or a random one for the `Unknown` variant.
+fn named<T: From<String> + Debug>(known: &'static [&'static str]) -> impl Strategy<Value = T> {
+    prop_oneof![select(known).prop_map(str::to_string), "[A-Za-z ]{0,12}"].prop_map(T::from)
+}
+
+fn item_type() -> impl Strategy<Value = ItemType> {
+    named(&[
+        "Weapon",
+        "Helmet",
+        "Pet",
+        "Wood Axe",
+        "Item Sprite",
+        "Event Item",
+    ])
+}
+
+fn item_rarity() -> impl Strategy<Value = ItemRarity> {
+    named(&[
+        "Common",
+        "Rare",
+        "Elite",
+        "Elilte",
+        "Legendary",
+        "Celestial",
+    ])
+}
+
+fn item_stat() -> impl Strategy<Value = Option<ItemStat>> {
+    proptest::option::of(named(&["str", "def", "dex", "crit", "hp"]))
+}
+
+/// The api's dates have no fractions of a second.
+fn date_time() -> impl Strategy<Value = DateTime<Utc>> {
+    (0..4_000_000_000i64).prop_map(|secs| Utc.timestamp_opt(secs, 0).unwrap())
+}
+
+fn item() -> impl Strategy<Value = Item> {
+    with_extra(
+        (
+            (
+                any::<u32>(),
+                any::<String>(),
+                item_type(),
+                // the api sends no description as an empty string
+                proptest::option::of(".+"),
+                any::<bool>(),
+                any::<u32>(),
+                item_rarity(),
+                any::<u32>(),
+            ),
+            (
+                item_stat(),
+                any::<Option<u32>>(),
+                item_stat(),
+                any::<Option<u32>>(),
+                item_stat(),
+                any::<Option<u32>>(),
+            ),
+            any::<[bool; 3]>(),
+        )
+            .prop_map(
+                |(
+                    (id, name, item_type, description, equipable, level, rarity, value),
+                    (stat1, stat1modifier, stat2, stat2modifier, stat3, stat3modifier),
+                    [custom_item, tradable, locked],
+                )| Item {
+                    id: ItemId::new(id),
+                    name,
+                    item_type,
+                    description,
+                    equipable,
+                    level,
+                    rarity,
+                    value,
+                    stat1,
+                    stat1modifier,
+                    stat2,
+                    stat2modifier,
+                    stat3,
+                    stat3modifier,
+                    custom_item,
+                    tradable,
+                    locked,
+                    #[cfg(feature = "extra-fields")]
+                    extra: Default::default(),
+                },
+            ),
+    )
+}
+
+fn equipped_item() -> impl Strategy<Value = EquippedItem> {
+    with_extra(
+        (
+            (
+                any::<u32>(),
+                any::<String>(),
+                item_type(),
+                any::<u32>(),
+                item_rarity(),
+            ),
+            (
+                item_stat(),
+                any::<Option<u32>>(),
+                item_stat(),
+                any::<Option<u32>>(),
+                item_stat(),
+                any::<Option<u32>>(),
+            ),
+        )
+            .prop_map(
+                |(
+                    (id, name, item_type, level, rarity),
+                    (stat1, stat1modifier, stat2, stat2modifier, stat3, stat3modifier),
+                )| EquippedItem {
+                    id: ItemId::new(id),
+                    name,
+                    item_type,
+                    level,
+                    rarity,
+                    stat1,
+                    stat1modifier,
+                    stat2,
+                    stat2modifier,
+                    stat3,
+                    stat3modifier,
+                    #[cfg(feature = "extra-fields")]
+                    extra: Default::default(),
+                },
+            ),
+    )
+}
+
+fn player_equipment() -> impl Strategy<Value = PlayerEquipment> {
+    with_extra(
+        proptest::collection::vec(proptest::option::of(equipped_item()), 9).prop_map(|slots| {
+            let mut slots = slots.into_iter();
+            let mut next = || slots.next().unwrap();
+            PlayerEquipment {
+                weapon: next(),
+                helmet: next(),
+                amulet: next(),
+                armour: next(),
+                shield: next(),
+                greaves: next(),
+                boots: next(),
+                special: next(),
+                pet: next(),
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            }
+        }),
+    )
+}
+
+fn pet() -> impl Strategy<Value = Pet> {
+    with_extra(
+        (
+            (any::<u32>(), any::<String>(), any::<u32>()),
+            (
+                item_stat(),
+                any::<Option<u32>>(),
+                item_stat(),
+                any::<Option<u32>>(),
+                item_stat(),
+                any::<Option<u32>>(),
+            ),
+        )
+            .prop_map(
+                |(
+                    (item_id, name, level),
+                    (stat1, stat1modifier, stat2, stat2modifier, stat3, stat3modifier),
+                )| Pet {
+                    item_id: ItemId::new(item_id),
+                    name,
+                    level,
+                    stat1,
+                    stat1modifier,
+                    stat2,
+                    stat2modifier,
+                    stat3,
+                    stat3modifier,
+                    #[cfg(feature = "extra-fields")]
+                    extra: Default::default(),
+                },
+            ),
+    )
+}
+
+fn player_skill() -> impl Strategy<Value = PlayerSkill> {
+    with_extra(
+        (
+            named::<Skill>(&[
+                "mining",
+                "woodcutting",
+                "fishing",
+                "crafting",
+                "treasure_hunting",
+            ]),
+            any::<u32>(),
+            any::<u64>(),
+        )
+            .prop_map(|(skill, level, exp)| PlayerSkill {
+                skill,
+                level,
+                exp,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            }),
+    )
+}
+
+fn guild() -> impl Strategy<Value = Guild> {
+    with_extra(
+        (
+            any::<u32>(),
+            any::<String>(),
+            any::<String>(),
+            any::<u32>(),
+            any::<u64>(),
+            any::<String>(),
+            any::<bool>(),
+            any::<u32>(),
+        )
+            .prop_map(
+                |(id, name, tag, leader, exp, icon, passive, member_count)| Guild {
+                    id: GuildId::new(id),
+                    name,
+                    tag,
+                    leader: UserId::new(leader),
+                    exp,
+                    icon,
+                    passive,
+                    member_count,
+                    #[cfg(feature = "extra-fields")]
+                    extra: Default::default(),
+                },
+            ),
+    )
+}
+
+fn guild_member() -> impl Strategy<Value = GuildMember> {
+    with_extra(
+        (
+            any::<u32>(),
+            any::<String>(),
+            any::<u32>(),
+            any::<String>(),
+            any::<bool>(),
+        )
+            .prop_map(|(user_id, name, level, position, safe_mode)| GuildMember {
+                user_id: UserId::new(user_id),
+                name,
+                level,
+                position,
+                safe_mode,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            }),
+    )
+}
+
+fn guild_war_side() -> impl Strategy<Value = GuildWarSide> {
+    with_extra(
+        (any::<u32>(), any::<String>(), any::<u32>()).prop_map(|(id, name, kills)| GuildWarSide {
+            id: GuildId::new(id),
+            name,
+            kills,
+            #[cfg(feature = "extra-fields")]
+            extra: Default::default(),
+        }),
+    )
+}
+
+fn guild_war() -> impl Strategy<Value = GuildWar> {
+    with_extra(
+        (
+            guild_war_side(),
+            guild_war_side(),
+            select(&[WarStatus::Ongoing, WarStatus::Hold, WarStatus::Ended][..]),
+        )
+            .prop_map(|(guild_1, guild_2, status)| GuildWar {
+                guild_1,
+                guild_2,
+                status,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            }),
+    )
+}
+
+fn inventory_entry() -> impl Strategy<Value = InventoryEntry> {
+    with_extra(
+        (any::<u32>(), any::<u32>(), any::<bool>(), any::<bool>()).prop_map(
+            |(item_id, quantity, equipped, locked)| InventoryEntry {
+                item_id: ItemId::new(item_id),
+                quantity,
+                equipped,
+                locked,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            },
+        ),
+    )
+}
+
+fn diamond_listing() -> impl Strategy<Value = DiamondListing> {
+    with_extra((any::<u32>(), any::<u64>(), any::<u32>()).prop_map(
+        |(seller, price_per_diamond, diamonds_remaining)| DiamondListing {
+            seller: UserId::new(seller),
+            price_per_diamond,
+            diamonds_remaining,
+            #[cfg(feature = "extra-fields")]
+            extra: Default::default(),
+        },
+    ))
+}
+
+fn orphanage() -> impl Strategy<Value = Orphanage> {
+    let recent_donator = with_extra((any::<u32>(), any::<u64>(), date_time()).prop_map(
+        |(user_id, amount, created_at)| RecentDonator {
+            user_id: UserId::new(user_id),
+            amount,
+            created_at,
+            #[cfg(feature = "extra-fields")]
+            extra: Default::default(),
+        },
+    ));
+    with_extra(
+        (
+            any::<u64>(),
+            any::<u64>(),
+            proptest::collection::vec(recent_donator, 0..4),
+        )
+            .prop_map(|(current_amount, max_amount, recent_donators)| Orphanage {
+                current_amount,
+                max_amount,
+                recent_donators,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            }),
+    )
+}
+
+fn smmo_player() -> impl Strategy<Value = SmmoPlayer> {
+    let guild =
+        with_extra(
+            (any::<u32>(), any::<String>()).prop_map(|(id, name)| SmmoPlayerGuild {
+                id: GuildId::new(id),
+                name,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            }),
+        );
+    with_extra((
+        (
+            any::<u32>(),
+            any::<String>(),
+            any::<u32>(),
+            any::<String>(),
+            any::<String>(),
+        ),
+        any::<[u32; 14]>(),
+        (
+            any::<bool>(),
+            proptest::option::of(date_time()),
+            any::<u32>(),
+            any::<u32>(),
+            proptest::option::of(guild),
+        ),
+    )
+        .prop_map(
+            |(
+                (id, name, level, motto, profile_number),
+                [exp, gold, steps, npc_kills, user_kills, quests_complete, dex, def, str, bonus_dex, bonus_def, bonus_str, hp, max_hp],
+                (safe_mode, safe_mode_time, background, membership, guild),
+            )| SmmoPlayer {
+                id: UserId::new(id),
+                name,
+                level,
+                motto,
+                profile_number,
+                exp,
+                gold,
+                steps,
+                npc_kills,
+                user_kills,
+                quests_complete,
+                dex,
+                def,
+                str,
+                bonus_dex,
+                bonus_def,
+                bonus_str,
+                hp,
+                max_hp,
+                safe_mode,
+                safe_mode_time,
+                background,
+                membership,
+                guild,
+                #[cfg(feature = "extra-fields")]
+                extra: Default::default(),
+            },
+        ))
+}
+
+fn self_player() -> impl Strategy<Value = SelfPlayer> {
+    (smmo_player(), any::<[u32; 5]>(), any::<u64>()).prop_map(
+        |(player, [energy, max_energy, quest_points, max_quest_points, diamonds], bank)| {
+            SelfPlayer {
+                player,
+                energy,
+                max_energy,
+                quest_points,
+                max_quest_points,
+                bank,
+                diamonds,
+            }
+        },
+    )
+}
+
+fn world_boss() -> impl Strategy<Value = WorldBoss> {
+    with_extra(
+        (
+            (any::<u32>(), any::<String>(), any::<String>()),
+            any::<[u32; 7]>(),
+            date_time(),
+        )
+            .prop_map(
+                |(
+                    (id, name, avatar),
+                    [level, god, str, def, dex, current_hp, max_hp],
+                    enable_time,
+                )| {
+                    WorldBoss {
+                        id,
+                        name,
+                        avatar,
+                        level,
+                        god,
+                        str,
+                        def,
+                        dex,
+                        current_hp,
+                        max_hp,
+                        enable_time,
+                        #[cfg(feature = "extra-fields")]
+                        extra: Default::default(),
+                    }
+                },
+            ),
+    )
+}
+
+fn list<T: Debug>(model: impl Strategy<Value = T>) -> impl Strategy<Value = Vec<T>> {
+    proptest::collection::vec(model, 0..4)
+}
+
+proptest! {
+    #[test]
+    fn test_item(item in item()) {
+        assert_round_trips(&item)?;
+    }
+
+    #[test]
+    fn test_player_equipment(equipment in player_equipment()) {
+        assert_round_trips(&equipment)?;
+    }
+
+    #[test]
+    fn test_player_pets(pets in list(pet())) {
+        assert_round_trips(&PlayerPets(pets))?;
+    }
+
+    #[test]
+    fn test_player_skills(skills in list(player_skill())) {
+        assert_round_trips(&PlayerSkills(skills))?;
+    }
+
+    #[test]
+    fn test_guild(guild in guild()) {
+        assert_round_trips(&guild)?;
+    }
+
+    #[test]
+    fn test_guild_members(members in list(guild_member())) {
+        assert_round_trips(&GuildMembers(members))?;
+    }
+
+    #[test]
+    fn test_guild_wars(wars in list(guild_war())) {
+        assert_round_trips(&GuildWars(wars))?;
+    }
+
+    #[test]
+    fn test_inventory(entries in list(inventory_entry())) {
+        assert_round_trips(&Inventory(entries))?;
+    }
+
+    #[test]
+    fn test_diamond_market(listings in list(diamond_listing())) {
+        assert_round_trips(&DiamondMarket(listings))?;
+    }
+
+    #[test]
+    fn test_orphanage(orphanage in orphanage()) {
+        assert_round_trips(&orphanage)?;
+    }
+
+    #[test]
+    fn test_smmo_player(player in smmo_player()) {
+        assert_round_trips(&player)?;
+    }
+
+    #[test]
+    fn test_self_player(player in self_player()) {
+        assert_round_trips(&player)?;
+    }
+
+    #[test]
+    fn test_world_bosses(bosses in list(world_boss())) {
+        assert_round_trips(&WorldBosses(bosses))?;
+    }
+}
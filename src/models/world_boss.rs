@@ -3,6 +3,8 @@ use std::fmt::{self, Display};
 use chrono::{serde::ts_seconds, DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "extra-fields")]
+use crate::models::extra::{ExtraFields, UnmappedFields};
 use crate::models::SmmoModel;
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -19,6 +21,16 @@ pub struct WorldBoss {
     pub max_hp: u32,
     #[serde(with = "ts_seconds")]
     pub enable_time: DateTime<Utc>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for WorldBoss {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.extra.collect_unmapped_fields(path, fields);
+    }
 }
 
 impl SmmoModel for WorldBoss {
@@ -34,6 +46,13 @@ impl Display for WorldBoss {
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct WorldBosses(pub Vec<WorldBoss>);
 
+#[cfg(feature = "extra-fields")]
+impl UnmappedFields for WorldBosses {
+    fn collect_unmapped_fields(&self, path: &str, fields: &mut Vec<String>) {
+        self.0.collect_unmapped_fields(path, fields);
+    }
+}
+
 impl SmmoModel for WorldBosses {
     const TYPE_NAME: &'static str = "Vec<WorldBoss>";
 }
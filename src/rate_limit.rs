@@ -0,0 +1,162 @@
+//! Client side rate limiting, to stay under the per-key request quota of the smmo api.
+
+use std::{sync::Mutex, time::Duration};
+
+use tokio::time::{sleep, Instant};
+
+/// A request quota: at most `requests` requests every `per`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    requests: u32,
+    per: Duration,
+}
+
+impl RateLimit {
+    /// # Panics
+    ///
+    /// Panics if `requests` or `per` is zero.
+    pub fn new(requests: u32, per: Duration) -> Self {
+        assert!(requests > 0, "rate limit must allow at least one request");
+        assert!(
+            per > Duration::from_secs(0),
+            "rate limit period must not be zero"
+        );
+        Self { requests, per }
+    }
+
+    /// At most `requests` requests every minute, the unit the smmo api quota is given in.
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    pub fn requests(&self) -> u32 {
+        self.requests
+    }
+
+    pub fn per(&self) -> Duration {
+        self.per
+    }
+
+    /// Tokens regained per second.
+    fn refill_rate(&self) -> f64 {
+        f64::from(self.requests) / self.per.as_secs_f64()
+    }
+}
+
+/// How long requests have had to wait for the rate limiter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitStats {
+    /// Number of requests that have passed through the limiter.
+    pub requests: u64,
+    /// How long the most recent request waited.
+    pub last_wait: Duration,
+    /// The longest any single request has waited.
+    pub max_wait: Duration,
+    /// The sum of all waits.
+    pub total_wait: Duration,
+}
+
+/// A token bucket, starting full. Waiting callers are served in the order they arrived.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    bucket: tokio::sync::Mutex<Bucket>,
+    stats: Mutex<RateLimitStats>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            bucket: tokio::sync::Mutex::new(Bucket {
+                tokens: f64::from(limit.requests),
+                last_refill: Instant::now(),
+            }),
+            stats: Mutex::new(RateLimitStats::default()),
+        }
+    }
+
+    pub(crate) fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    pub(crate) fn stats(&self) -> RateLimitStats {
+        *self.stats.lock().unwrap()
+    }
+
+    /// Waits until a request may be sent, returning how long that took.
+    pub(crate) async fn acquire(&self) -> Duration {
+        let start = Instant::now();
+
+        // the lock is held while sleeping so that later callers queue up behind this one.
+        let mut bucket = self.bucket.lock().await;
+        self.refill(&mut bucket);
+        if bucket.tokens < 1.0 {
+            let missing = 1.0 - bucket.tokens;
+            sleep(Duration::from_secs_f64(missing / self.limit.refill_rate())).await;
+            self.refill(&mut bucket);
+        }
+        bucket.tokens -= 1.0;
+        drop(bucket);
+
+        let waited = start.elapsed();
+        let mut stats = self.stats.lock().unwrap();
+        stats.requests += 1;
+        stats.last_wait = waited;
+        stats.max_wait = stats.max_wait.max(waited);
+        stats.total_wait += waited;
+
+        waited
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.limit.refill_rate())
+            .min(f64::from(self.limit.requests));
+        bucket.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod test_rate_limiter {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_burst_then_wait() {
+        let limiter = RateLimiter::new(RateLimit::new(2, Duration::from_secs(1)));
+
+        assert_eq!(limiter.acquire().await, Duration::from_secs(0));
+        assert_eq!(limiter.acquire().await, Duration::from_secs(0));
+        let waited = limiter.acquire().await;
+        assert!(waited >= Duration::from_millis(499), "{:?}", waited);
+        assert!(waited <= Duration::from_millis(501), "{:?}", waited);
+
+        let stats = limiter.stats();
+        assert_eq!(stats.requests, 3);
+        assert_eq!(stats.last_wait, waited);
+        assert_eq!(stats.max_wait, waited);
+        assert_eq!(stats.total_wait, waited);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_refills_over_time() {
+        let limiter = RateLimiter::new(RateLimit::per_minute(1));
+
+        assert_eq!(limiter.acquire().await, Duration::from_secs(0));
+        sleep(Duration::from_secs(60)).await;
+        assert_eq!(limiter.acquire().await, Duration::from_secs(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_requests() {
+        RateLimit::per_minute(0);
+    }
+}
@@ -0,0 +1,101 @@
+//! Keeping the api key out of urls, errors, logs and `Debug` output.
+
+use std::fmt::{self, Debug};
+
+use reqwest::Url;
+
+/// The name of the parameter the api key is sent in.
+pub(crate) const API_KEY_PARAM: &str = "api_key";
+
+const REDACTED: &str = "[REDACTED]";
+
+/// An api key that is never shown in `Debug` output.
+#[derive(Clone)]
+pub(crate) struct ApiKey(String);
+
+impl ApiKey {
+    pub(crate) fn new(key: String) -> Self {
+        Self(key)
+    }
+
+    pub(crate) fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+/// Replaces the value of the api key query parameter, if there is one.
+pub(crate) fn redact_url(url: &Url) -> Url {
+    if !url.query_pairs().any(|(key, _)| key == API_KEY_PARAM) {
+        return url.clone();
+    }
+
+    let pairs = url
+        .query_pairs()
+        .map(|(key, value)| {
+            if key == API_KEY_PARAM {
+                (key.into_owned(), REDACTED.to_string())
+            } else {
+                (key.into_owned(), value.into_owned())
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut redacted = url.clone();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    redacted
+}
+
+/// Redacts the url that reqwest attaches to its errors, which shows up in both their `Display`
+/// and `Debug` output.
+pub(crate) fn redact_error(mut error: reqwest::Error) -> reqwest::Error {
+    if let Some(url) = error.url_mut() {
+        *url = redact_url(url);
+    }
+    error
+}
+
+#[cfg(test)]
+mod test_redact {
+    use super::*;
+
+    #[test]
+    fn test_redact_url() {
+        let url = Url::parse("https://api.simple-mmo.com/v1/orphanage?api_key=secret").unwrap();
+        assert_eq!(
+            redact_url(&url).as_str(),
+            "https://api.simple-mmo.com/v1/orphanage?api_key=%5BREDACTED%5D"
+        );
+    }
+
+    #[test]
+    fn test_redact_url_keeps_other_params() {
+        let url = Url::parse("http://localhost/v1/item/info/1?a=1&api_key=secret&b=2").unwrap();
+        let redacted = redact_url(&url);
+        assert!(!redacted.as_str().contains("secret"));
+        assert_eq!(
+            redacted.query_pairs().collect::<Vec<_>>(),
+            vec![
+                ("a".into(), "1".into()),
+                ("api_key".into(), REDACTED.into()),
+                ("b".into(), "2".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redact_url_without_key() {
+        let url = Url::parse("http://localhost/v1/orphanage").unwrap();
+        assert_eq!(redact_url(&url), url);
+    }
+
+    #[test]
+    fn test_api_key_debug() {
+        assert_eq!(format!("{:?}", ApiKey::new("secret".into())), "[REDACTED]");
+    }
+}
@@ -0,0 +1,219 @@
+//! Retrying requests that failed for transient reasons.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    StatusCode,
+};
+
+/// How often, and how patiently, to retry requests that failed for transient reasons.
+///
+/// A request is retried when it could not be sent or its response could not be read (connection
+/// errors, timeouts, ...), or when the api responds with `429 Too Many Requests` or any `5xx`
+/// status. Authentication errors are never retried.
+///
+/// Between attempts the client waits for the duration in the `Retry-After` header if the
+/// response had one (but no longer than `max_retry_after`), otherwise it backs off
+/// exponentially: `initial_backoff`, then twice that, and so on, up to `max_backoff`. With jitter
+/// enabled, each backoff is randomly shortened by up to half so that clients that failed
+/// together don't all retry at the same moment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_retry_after: Duration,
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Make at most `max_attempts` attempts in total, backing off from 500ms up to 30s with
+    /// jitter, and waiting at most a minute for `Retry-After`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_attempts` is zero.
+    pub fn new(max_attempts: u32) -> Self {
+        assert!(max_attempts > 0, "at least one attempt must be made");
+        Self {
+            max_attempts,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_retry_after: Duration::from_secs(60),
+            jitter: true,
+        }
+    }
+
+    /// Never retry; every request is attempted exactly once. This is the default.
+    pub fn never() -> Self {
+        Self::new(1)
+    }
+
+    /// The backoff before the first retry.
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// The longest backoff between two attempts. Does not limit `Retry-After`; see
+    /// [`max_retry_after`](Self::max_retry_after).
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// The longest the server can make the client wait with `Retry-After`. Longer waits are
+    /// cut short, so that a bad header can't stall a request (and every clone of the client
+    /// waiting on the same rate limiter) for hours.
+    pub fn max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// How long to wait after the given (1-based) failed attempt, whose response had `headers`.
+    pub(crate) fn delay(&self, headers: &HeaderMap, attempt: u32) -> Duration {
+        retry_after(headers).map_or_else(
+            || self.backoff(attempt),
+            |retry_after| retry_after.min(self.max_retry_after),
+        )
+    }
+
+    /// The backoff after the given (1-based) failed attempt.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let backoff = self
+            .initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+
+        if self.jitter {
+            let millis = backoff.as_millis() as u64;
+            Duration::from_millis(rand::thread_rng().gen_range(millis / 2..=millis))
+        } else {
+            backoff
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::never()
+    }
+}
+
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+pub(crate) fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request() || error.is_body()
+}
+
+/// Parses the `Retry-After` header, which is either a number of seconds or an http date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod test_retry_policy {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy::new(10)
+            .initial_backoff(Duration::from_secs(1))
+            .max_backoff(Duration::from_secs(5))
+            .jitter(false);
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(4), Duration::from_secs(5));
+        assert_eq!(policy.backoff(100), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_backoff_jitter() {
+        let policy = RetryPolicy::new(10).initial_backoff(Duration::from_secs(2));
+
+        for _ in 0..100 {
+            let backoff = policy.backoff(1);
+            assert!(backoff >= Duration::from_secs(1));
+            assert!(backoff <= Duration::from_secs(2));
+        }
+    }
+
+    #[test]
+    fn test_retry_after_seconds() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn test_retry_after_date_in_past() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Sun, 06 Nov 1994 08:49:37 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(0)));
+    }
+
+    #[test]
+    fn test_retry_after_invalid() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_retry_after_is_clamped() {
+        let policy = RetryPolicy::new(3)
+            .initial_backoff(Duration::from_secs(1))
+            .max_retry_after(Duration::from_secs(10))
+            .jitter(false);
+        let mut headers = HeaderMap::new();
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("5"));
+        assert_eq!(policy.delay(&headers, 1), Duration::from_secs(5));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("36000"));
+        assert_eq!(policy.delay(&headers, 1), Duration::from_secs(10));
+
+        assert_eq!(policy.delay(&HeaderMap::new(), 1), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_retryable_status() {
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+        assert!(!is_retryable_status(StatusCode::OK));
+    }
+}
@@ -0,0 +1,312 @@
+//! Finding items by name, for when users type names rather than ids.
+
+use crate::models::item::{Item, ItemId, ItemRarity, ItemStat, ItemType};
+
+/// An in-memory index over a collection of items, searchable by name.
+///
+/// ```
+/// # use smmo_api::search::{ItemFilter, ItemIndex};
+/// # fn search(items: &[smmo_api::models::item::Item]) {
+/// let index = ItemIndex::new(items);
+/// let ids = index.search("wooden stik", &ItemFilter::new());
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ItemIndex {
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    item: Item,
+    lowercase_name: String,
+}
+
+/// How a name matched a query, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    Exact,
+    CaseInsensitive,
+    /// The name starts with the query, ignoring case.
+    Prefix,
+    /// The name is within `distance` single character edits of the query, ignoring case.
+    Fuzzy {
+        distance: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub id: ItemId,
+    pub kind: MatchKind,
+}
+
+impl ItemIndex {
+    pub fn new<'a>(items: impl IntoIterator<Item = &'a Item>) -> Self {
+        Self {
+            entries: items
+                .into_iter()
+                .map(|item| Entry {
+                    item: item.clone(),
+                    lowercase_name: item.name.to_lowercase(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The ids of the items matching the query and the filter, best match first.
+    pub fn search(&self, query: &str, filter: &ItemFilter) -> Vec<ItemId> {
+        self.search_matches(query, filter)
+            .into_iter()
+            .map(|search_match| search_match.id)
+            .collect()
+    }
+
+    /// Like [`search`](Self::search), but also says how each item matched.
+    ///
+    /// Typos are tolerated in proportion to the length of the query: one edit for every four
+    /// characters, and at least one. An empty query matches every item that passes the filter.
+    /// Matches of the same kind are ordered by name, then id.
+    pub fn search_matches(&self, query: &str, filter: &ItemFilter) -> Vec<SearchMatch> {
+        let query = query.trim();
+        let lowercase_query = query.to_lowercase();
+        let max_distance = (lowercase_query.chars().count() / 4).max(1);
+
+        let mut matches = self
+            .entries
+            .iter()
+            .filter(|entry| filter.matches(&entry.item))
+            .filter_map(|entry| {
+                let kind = if query.is_empty() || entry.item.name == query {
+                    MatchKind::Exact
+                } else if entry.lowercase_name == lowercase_query {
+                    MatchKind::CaseInsensitive
+                } else if entry.lowercase_name.starts_with(&lowercase_query) {
+                    MatchKind::Prefix
+                } else {
+                    let distance = levenshtein(&entry.lowercase_name, &lowercase_query);
+                    if distance > max_distance {
+                        return None;
+                    }
+                    MatchKind::Fuzzy { distance }
+                };
+                Some((kind, entry))
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|(a_kind, a), (b_kind, b)| {
+            a_kind
+                .cmp(b_kind)
+                .then_with(|| a.item.name.cmp(&b.item.name))
+                .then_with(|| a.item.id.cmp(&b.item.id))
+        });
+
+        matches
+            .into_iter()
+            .map(|(kind, entry)| SearchMatch {
+                id: entry.item.id,
+                kind,
+            })
+            .collect()
+    }
+}
+
+/// Restricts search results to items with the given properties. Every property is optional, and
+/// the default filter lets everything through.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemFilter {
+    item_type: Option<ItemType>,
+    rarity: Option<ItemRarity>,
+    min_level: Option<u32>,
+    max_level: Option<u32>,
+    stat: Option<ItemStat>,
+}
+
+impl ItemFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn item_type(mut self, item_type: ItemType) -> Self {
+        self.item_type = Some(item_type);
+        self
+    }
+
+    pub fn rarity(mut self, rarity: ItemRarity) -> Self {
+        self.rarity = Some(rarity);
+        self
+    }
+
+    /// Only items of at least this level.
+    pub fn min_level(mut self, min_level: u32) -> Self {
+        self.min_level = Some(min_level);
+        self
+    }
+
+    /// Only items of at most this level.
+    pub fn max_level(mut self, max_level: u32) -> Self {
+        self.max_level = Some(max_level);
+        self
+    }
+
+    /// Only items that have this stat in any of their stat slots.
+    pub fn stat(mut self, stat: ItemStat) -> Self {
+        self.stat = Some(stat);
+        self
+    }
+
+    pub fn matches(&self, item: &Item) -> bool {
+        self.item_type
+            .as_ref()
+            .is_none_or(|item_type| item.item_type == *item_type)
+            && self
+                .rarity
+                .as_ref()
+                .is_none_or(|rarity| item.rarity == *rarity)
+            && self.min_level.is_none_or(|min| item.level >= min)
+            && self.max_level.is_none_or(|max| item.level <= max)
+            && self.stat.as_ref().is_none_or(|stat| {
+                [&item.stat1, &item.stat2, &item.stat3]
+                    .iter()
+                    .any(|item_stat| item_stat.as_ref() == Some(stat))
+            })
+    }
+}
+
+/// The number of single character insertions, deletions and substitutions needed to turn one
+/// string into the other.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = (previous[j + 1] + 1).min(current[j] + 1).min(substitution);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod test_item_index {
+    use super::*;
+
+    fn item(id: u32, name: &str, item_type: ItemType, rarity: ItemRarity, level: u32) -> Item {
+        Item {
+            id: ItemId::new(id),
+            name: name.to_string(),
+            item_type,
+            description: None,
+            equipable: true,
+            level,
+            rarity,
+            value: 20,
+            stat1: Some(ItemStat::Str),
+            stat1modifier: Some(1),
+            stat2: None,
+            stat2modifier: None,
+            stat3: None,
+            stat3modifier: None,
+            custom_item: false,
+            tradable: true,
+            locked: false,
+            #[cfg(feature = "extra-fields")]
+            extra: Default::default(),
+        }
+    }
+
+    fn index() -> ItemIndex {
+        let mut helmet = item(4, "Wooden Helmet", ItemType::Helmet, ItemRarity::Common, 5);
+        helmet.stat1 = Some(ItemStat::Def);
+        ItemIndex::new(&[
+            item(1, "Wooden Stick", ItemType::Weapon, ItemRarity::Common, 1),
+            item(2, "wooden stick", ItemType::Weapon, ItemRarity::Rare, 50),
+            item(3, "Wooden Sticks", ItemType::Weapon, ItemRarity::Common, 10),
+            helmet,
+            item(5, "Iron Sword", ItemType::Weapon, ItemRarity::Epic, 100),
+        ])
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("stick", ""), 5);
+        assert_eq!(levenshtein("stick", "stik"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_ranking() {
+        let matches = index().search_matches("Wooden Stick", &ItemFilter::new());
+        assert_eq!(
+            matches,
+            vec![
+                SearchMatch {
+                    id: ItemId::new(1),
+                    kind: MatchKind::Exact
+                },
+                SearchMatch {
+                    id: ItemId::new(2),
+                    kind: MatchKind::CaseInsensitive
+                },
+                SearchMatch {
+                    id: ItemId::new(3),
+                    kind: MatchKind::Prefix
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typo() {
+        assert_eq!(
+            index().search("wooden stik", &ItemFilter::new()),
+            vec![ItemId::new(1), ItemId::new(2), ItemId::new(3)]
+        );
+        assert!(index()
+            .search("plastic fork", &ItemFilter::new())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_prefix() {
+        assert_eq!(
+            index().search("iron", &ItemFilter::new()),
+            vec![ItemId::new(5)]
+        );
+    }
+
+    #[test]
+    fn test_filters() {
+        let index = index();
+        assert_eq!(
+            index.search("wooden", &ItemFilter::new().item_type(ItemType::Helmet)),
+            vec![ItemId::new(4)]
+        );
+        assert_eq!(
+            index.search("wooden stick", &ItemFilter::new().rarity(ItemRarity::Rare)),
+            vec![ItemId::new(2)]
+        );
+        assert_eq!(
+            index.search("wooden", &ItemFilter::new().min_level(5).max_level(10)),
+            vec![ItemId::new(4), ItemId::new(3)]
+        );
+        assert_eq!(
+            index.search("", &ItemFilter::new().stat(ItemStat::Def)),
+            vec![ItemId::new(4)]
+        );
+    }
+}
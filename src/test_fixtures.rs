@@ -0,0 +1,92 @@
+//! Api responses shared between tests, in the api's own format.
+
+pub(crate) const ITEM: &str = r#"{
+    "id": 1,
+    "name": "Wooden Stick",
+    "type": "Weapon",
+    "description": "",
+    "equipable": "1",
+    "level": 1,
+    "rarity": "Common",
+    "value": 20,
+    "stat1": "str",
+    "stat1modifier": 1,
+    "stat2": null,
+    "stat2modifier": 0,
+    "stat3": null,
+    "stat3modifier": null,
+    "custom_item": 0,
+    "tradable": 1,
+    "locked": 0
+}"#;
+
+/// Empty slots are left out, or sent as `null`.
+pub(crate) const PLAYER_EQUIPMENT: &str = r#"{
+    "weapon": {
+        "id": 1,
+        "name": "Wooden Stick",
+        "type": "Weapon",
+        "level": 1,
+        "rarity": "Common",
+        "stat1": "str",
+        "stat1modifier": 1,
+        "stat2": null,
+        "stat2modifier": 0,
+        "stat3": null,
+        "stat3modifier": null
+    },
+    "helmet": null,
+    "pet": {
+        "id": 1500,
+        "name": "Baby Dragon",
+        "type": "Pet",
+        "level": 12,
+        "rarity": "Rare",
+        "stat1": "str",
+        "stat1modifier": 25,
+        "stat2": "hp",
+        "stat2modifier": 100,
+        "stat3": null,
+        "stat3modifier": null
+    },
+    "boots": null
+}"#;
+
+pub(crate) const SELF_PLAYER: &str = r#"{
+    "id": 1234,
+    "name": "someone",
+    "level": 150,
+    "motto": "hello",
+    "profile_number": "1234",
+    "exp": 100000,
+    "gold": 500,
+    "steps": 20000,
+    "npc_kills": 3000,
+    "user_kills": 12,
+    "quests_complete": 400,
+    "dex": 50,
+    "def": 60,
+    "str": 70,
+    "bonus_dex": 5,
+    "bonus_def": 6,
+    "bonus_str": 7,
+    "hp": 900,
+    "max_hp": 1000,
+    "safeMode": 1,
+    "safeModeTime": "2021-04-01 12:30:00",
+    "background": 0,
+    "membership": 1,
+    "guild": { "id": 7, "name": "The Lost" },
+    "energy": 8,
+    "max_energy": 10,
+    "quest_points": 3,
+    "max_quest_points": 5,
+    "bank": 1000000,
+    "diamonds": 25
+}"#;
+
+pub(crate) const ORPHANAGE: &str = r#"{
+    "current_amount": 10,
+    "max_amount": 100,
+    "recent_donators": []
+}"#;
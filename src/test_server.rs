@@ -0,0 +1,199 @@
+//! A tiny http server standing in for the smmo api in tests.
+//!
+//! Every request is recorded so that tests can check exactly what went over the wire, and is
+//! answered with the next queued response (the last one is repeated once the queue runs dry).
+
+use std::{
+    collections::VecDeque,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use reqwest::Url;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct RecordedRequest {
+    pub method: String,
+    /// The path and query, exactly as sent in the request line.
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| &**value)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// How long to wait before answering.
+    pub delay: Duration,
+}
+
+impl MockResponse {
+    pub fn json(body: &str) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: body.into(),
+            delay: Duration::from_secs(0),
+        }
+    }
+
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+#[derive(Default)]
+struct State {
+    requests: Vec<RecordedRequest>,
+    responses: VecDeque<MockResponse>,
+    in_flight: usize,
+    max_in_flight: usize,
+}
+
+pub(crate) struct TestServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+}
+
+impl TestServer {
+    /// Starts a server that answers every request with the given responses, in order.
+    pub async fn start(responses: Vec<MockResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(State {
+            requests: vec![],
+            responses: responses.into(),
+            in_flight: 0,
+            max_in_flight: 0,
+        }));
+
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                tokio::spawn(handle(stream, server_state.clone()));
+            }
+        });
+
+        Self { addr, state }
+    }
+
+    /// The url of the server, suitable for use as a base url.
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}/v1/", self.addr)).unwrap()
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// The most requests that were being answered at the same time.
+    pub fn max_in_flight(&self) -> usize {
+        self.state.lock().unwrap().max_in_flight
+    }
+}
+
+async fn handle(mut stream: TcpStream, state: Arc<Mutex<State>>) {
+    let mut buf = Vec::new();
+    let header_end = loop {
+        let mut chunk = [0; 1024];
+        let read = match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(read) => read,
+        };
+        buf.extend_from_slice(&chunk[..read]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default().to_string();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect::<Vec<_>>();
+
+    let content_length = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let mut chunk = [0; 1024];
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(read) => body.extend_from_slice(&chunk[..read]),
+        }
+    }
+
+    let response = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(RecordedRequest {
+            method,
+            target,
+            headers,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        });
+        state.in_flight += 1;
+        state.max_in_flight = state.max_in_flight.max(state.in_flight);
+        if state.responses.len() > 1 {
+            state.responses.pop_front().unwrap()
+        } else {
+            state
+                .responses
+                .front()
+                .cloned()
+                .unwrap_or_else(|| MockResponse::json("{}").status(404))
+        }
+    };
+
+    tokio::time::sleep(response.delay).await;
+
+    let mut out = format!("HTTP/1.1 {} Mock\r\n", response.status);
+    for (key, value) in &response.headers {
+        out.push_str(&format!("{}: {}\r\n", key, value));
+    }
+    out.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.body.len(),
+        response.body
+    ));
+    let _ = stream.write_all(out.as_bytes()).await;
+    let _ = stream.shutdown().await;
+    state.lock().unwrap().in_flight -= 1;
+}